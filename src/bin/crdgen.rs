@@ -0,0 +1,18 @@
+// Copyright 2025, Jeroen van Erp <jeroen@geeko.me>
+// SPDX-License-Identifier: Apache-2.0
+use kube::CustomResourceExt;
+
+use sprouter::types::{ClusterSeed, SproutPolicy, SproutRequest, SproutStatus};
+
+/// Prints the CustomResourceDefinitions of Sprouter, for inclusion in the Helm chart.
+fn main() -> anyhow::Result<()> {
+    for crd in [
+        SproutRequest::crd(),
+        SproutPolicy::crd(),
+        ClusterSeed::crd(),
+        SproutStatus::crd(),
+    ] {
+        print!("---\n{}", serde_yaml::to_string(&crd)?);
+    }
+    Ok(())
+}
@@ -0,0 +1,164 @@
+// Copyright 2025, Jeroen van Erp <jeroen@geeko.me>
+// SPDX-License-Identifier: Apache-2.0
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+
+use crate::sprout::target::{Pattern, parse_patterns};
+
+const EXCLUDED_NAMESPACES_ENV: &str = "SPROUTER_EXCLUDED_NAMESPACES";
+const OPT_IN_ENV: &str = "SPROUTER_OPT_IN";
+const METRICS_ADDR_ENV: &str = "SPROUTER_METRICS_ADDR";
+const HEALTH_ADDR_ENV: &str = "SPROUTER_HEALTH_ADDR";
+const LIVENESS_THRESHOLD_ENV: &str = "SPROUTER_LIVENESS_THRESHOLD_SECONDS";
+const LEADER_ELECTION_ENV: &str = "SPROUTER_LEADER_ELECTION";
+const LEASE_NAME_ENV: &str = "SPROUTER_LEASE_NAME";
+const SHUTDOWN_TIMEOUT_ENV: &str = "SPROUTER_SHUTDOWN_TIMEOUT_SECONDS";
+const RESYNC_INTERVAL_ENV: &str = "SPROUTER_RESYNC_INTERVAL_SECONDS";
+const CONCURRENCY_ENV: &str = "SPROUTER_CONCURRENCY";
+const SEED_CONCURRENCY_ENV: &str = "SPROUTER_SEED_CONCURRENCY";
+const QPS_ENV: &str = "SPROUTER_QPS";
+const BURST_ENV: &str = "SPROUTER_BURST";
+const GC_INTERVAL_ENV: &str = "SPROUTER_GC_INTERVAL_SECONDS";
+const GC_DRY_RUN_ENV: &str = "SPROUTER_GC_DRY_RUN";
+const DEFAULT_EXCLUDED_NAMESPACES: &str = "kube-system,kube-public,kube-node-lease";
+const DEFAULT_METRICS_ADDR: &str = "0.0.0.0:8080";
+const DEFAULT_HEALTH_ADDR: &str = "0.0.0.0:8081";
+const DEFAULT_LIVENESS_THRESHOLD: Duration = Duration::from_secs(300);
+const DEFAULT_LEASE_NAME: &str = "sprouter";
+const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(25);
+const DEFAULT_RESYNC_INTERVAL: Duration = Duration::from_secs(600);
+const DEFAULT_CONCURRENCY: usize = 16;
+const DEFAULT_SEED_CONCURRENCY: u16 = 4;
+const DEFAULT_QPS: f64 = 50.0;
+const DEFAULT_BURST: u32 = 100;
+const DEFAULT_GC_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// Operator-wide settings, read from the environment at startup.
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// Namespaces no seed will ever sprout into.
+    pub excluded_namespaces: Vec<Pattern>,
+    /// Only sprout into namespaces labelled `sprouter.geeko.me/receive=true`.
+    pub opt_in: bool,
+    /// The address `/metrics` is served on.
+    pub metrics_addr: SocketAddr,
+    /// The address `/healthz` and `/readyz` are served on.
+    pub health_addr: SocketAddr,
+    /// How long a watcher may fail or wait for its first event before liveness fails.
+    pub liveness_threshold: Duration,
+    /// Only the replica holding the lease is active, the others stand by.
+    pub leader_election: bool,
+    /// The name of the Lease, in the namespace of the operator.
+    pub lease_name: String,
+    /// How long in-flight propagation may take to finish once a shutdown is signalled.
+    pub shutdown_timeout: Duration,
+    /// How often every seed is reconciled, even if it did not change.
+    pub resync_interval: Duration,
+    /// How many namespaces a seed is sprouted into at the same time.
+    pub concurrency: usize,
+    /// How many ConfigMap and Secret seeds are reconciled at the same time, per kind.
+    pub seed_concurrency: u16,
+    /// API requests per second, shared by all seeds. Watches and the leader election Lease are not
+    /// limited, so the lease can always be renewed. Zero disables the limit.
+    pub qps: f64,
+    /// API requests that may be made at once before `qps` applies.
+    pub burst: u32,
+    /// How often sprouts of seeds that are gone are collected, besides at startup. Zero disables
+    /// the periodic collection.
+    pub gc_interval: Duration,
+    /// Only report orphaned sprouts instead of deleting them.
+    pub gc_dry_run: bool,
+}
+
+impl Config {
+    pub fn from_env() -> Result<Self> {
+        let excluded = std::env::var(EXCLUDED_NAMESPACES_ENV)
+            .unwrap_or_else(|_| DEFAULT_EXCLUDED_NAMESPACES.to_string());
+        Ok(Self {
+            excluded_namespaces: parse_patterns(&excluded)
+                .with_context(|| format!("Invalid {}", EXCLUDED_NAMESPACES_ENV))?,
+            opt_in: bool_from_env(OPT_IN_ENV)?,
+            metrics_addr: addr_from_env(METRICS_ADDR_ENV, DEFAULT_METRICS_ADDR)?,
+            health_addr: addr_from_env(HEALTH_ADDR_ENV, DEFAULT_HEALTH_ADDR)?,
+            liveness_threshold: secs_from_env(LIVENESS_THRESHOLD_ENV, DEFAULT_LIVENESS_THRESHOLD)?,
+            leader_election: bool_from_env(LEADER_ELECTION_ENV)?,
+            lease_name: std::env::var(LEASE_NAME_ENV)
+                .unwrap_or_else(|_| DEFAULT_LEASE_NAME.to_string()),
+            shutdown_timeout: secs_from_env(SHUTDOWN_TIMEOUT_ENV, DEFAULT_SHUTDOWN_TIMEOUT)?,
+            resync_interval: secs_from_env(RESYNC_INTERVAL_ENV, DEFAULT_RESYNC_INTERVAL)?,
+            concurrency: num_from_env(CONCURRENCY_ENV, DEFAULT_CONCURRENCY)?.max(1),
+            seed_concurrency: num_from_env(SEED_CONCURRENCY_ENV, DEFAULT_SEED_CONCURRENCY)?,
+            qps: num_from_env(QPS_ENV, DEFAULT_QPS)?,
+            burst: num_from_env(BURST_ENV, DEFAULT_BURST)?,
+            gc_interval: secs_from_env(GC_INTERVAL_ENV, DEFAULT_GC_INTERVAL)?,
+            gc_dry_run: bool_from_env(GC_DRY_RUN_ENV)?,
+        })
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            excluded_namespaces: parse_patterns(DEFAULT_EXCLUDED_NAMESPACES)
+                .expect("default exclusions are valid patterns"),
+            opt_in: false,
+            metrics_addr: DEFAULT_METRICS_ADDR
+                .parse()
+                .expect("default address is valid"),
+            health_addr: DEFAULT_HEALTH_ADDR
+                .parse()
+                .expect("default address is valid"),
+            liveness_threshold: DEFAULT_LIVENESS_THRESHOLD,
+            leader_election: false,
+            lease_name: DEFAULT_LEASE_NAME.to_string(),
+            shutdown_timeout: DEFAULT_SHUTDOWN_TIMEOUT,
+            resync_interval: DEFAULT_RESYNC_INTERVAL,
+            concurrency: DEFAULT_CONCURRENCY,
+            seed_concurrency: DEFAULT_SEED_CONCURRENCY,
+            qps: DEFAULT_QPS,
+            burst: DEFAULT_BURST,
+            gc_interval: DEFAULT_GC_INTERVAL,
+            gc_dry_run: false,
+        }
+    }
+}
+
+fn bool_from_env(key: &str) -> Result<bool> {
+    match std::env::var(key) {
+        Ok(v) => v
+            .parse()
+            .with_context(|| format!("Invalid {}: '{}'", key, v)),
+        Err(_) => Ok(false),
+    }
+}
+
+fn addr_from_env(key: &str, default: &str) -> Result<SocketAddr> {
+    let v = std::env::var(key).unwrap_or_else(|_| default.to_string());
+    v.parse()
+        .with_context(|| format!("Invalid {}: '{}'", key, v))
+}
+
+fn secs_from_env(key: &str, default: Duration) -> Result<Duration> {
+    match std::env::var(key) {
+        Ok(v) => v
+            .parse()
+            .map(Duration::from_secs)
+            .with_context(|| format!("Invalid {}: '{}'", key, v)),
+        Err(_) => Ok(default),
+    }
+}
+
+fn num_from_env<T>(key: &str, default: T) -> Result<T>
+where
+    T: std::str::FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    match std::env::var(key) {
+        Ok(v) => v
+            .parse()
+            .with_context(|| format!("Invalid {}: '{}'", key, v)),
+        Err(_) => Ok(default),
+    }
+}
@@ -0,0 +1,81 @@
+// Copyright 2025, Jeroen van Erp <jeroen@geeko.me>
+// SPDX-License-Identifier: Apache-2.0
+use futures::StreamExt;
+use kube::ResourceExt;
+use kube::{Api, Client};
+use kube_runtime::WatchStreamExt;
+use kube_runtime::watcher::{Config as WatcherConfig, Event, watcher};
+
+use tracing::{info, warn};
+
+use crate::controller::retry::{Retries, key_of};
+use crate::health::HEALTH;
+use crate::metrics::METRICS;
+use crate::shutdown::SHUTDOWN;
+use crate::sprout::manager::SproutManager;
+use crate::types::ClusterSeed;
+
+pub async fn run(client: Client, sprout_manager: &SproutManager) -> anyhow::Result<()> {
+    let api: Api<ClusterSeed> = Api::all(client.clone());
+    let mut watcher = watcher(api, WatcherConfig::default())
+        .default_backoff()
+        .take_until(SHUTDOWN.wait())
+        .boxed();
+    let mut retries = Retries::default();
+
+    info!("Starting ClusterSeed watcher...");
+    HEALTH.watcher_started("ClusterSeed");
+    loop {
+        let event = tokio::select! {
+            event = watcher.next() => match event {
+                Some(Ok(event)) => {
+                    HEALTH.watcher_progressed("ClusterSeed");
+                    event
+                }
+                Some(Err(e)) => {
+                    warn!("ClusterSeed watcher failed, restarting with backoff: {}", e);
+                    METRICS.record_watcher_restart("ClusterSeed");
+                    HEALTH.watcher_failed("ClusterSeed");
+                    continue;
+                }
+                None => break,
+            },
+            event = retries.next() => event,
+        };
+        let Some(key) = key_of(&event) else {
+            continue;
+        };
+        match handle(sprout_manager, &event).await {
+            Ok(()) => retries.succeeded(&key),
+            Err(e) => {
+                METRICS.record_reconcile_error("ClusterSeed");
+                let delay = retries.failed(&key, event);
+                warn!(
+                    "Handling ClusterSeed '{}' failed, retrying in {:?}: {}",
+                    key, delay, e
+                );
+            }
+        }
+    }
+    HEALTH.watcher_stopped("ClusterSeed");
+    info!("ClusterSeed watcher stopped.");
+
+    Ok(())
+}
+
+async fn handle(sprout_manager: &SproutManager, event: &Event<ClusterSeed>) -> anyhow::Result<()> {
+    match event {
+        Event::Apply(seed) | Event::InitApply(seed) => {
+            if let Event::Apply(_) = event {
+                sprout_manager.change_observed("ClusterSeed", &seed.name_any());
+            }
+            sprout_manager.apply_cluster_seed(seed).await?;
+        }
+        Event::Delete(seed) => {
+            info!("ClusterSeed '{}' deleted", seed.name_any());
+            sprout_manager.delete_cluster_seed(seed).await?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
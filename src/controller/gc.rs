@@ -0,0 +1,28 @@
+// Copyright 2025, Jeroen van Erp <jeroen@geeko.me>
+// SPDX-License-Identifier: Apache-2.0
+use tracing::{info, warn};
+
+use crate::shutdown::SHUTDOWN;
+use crate::sprout::manager::SproutManager;
+
+/// Collects the sprouts of seeds that are gone on the configured interval, until shutdown.
+pub async fn run(sprout_manager: &SproutManager) -> anyhow::Result<()> {
+    let interval = sprout_manager.config().gc_interval;
+    if interval.is_zero() {
+        return Ok(());
+    }
+
+    info!("Collecting orphaned sprouts every {:?}", interval);
+    loop {
+        tokio::select! {
+            _ = tokio::time::sleep(interval) => {}
+            _ = SHUTDOWN.wait() => break,
+        }
+        if let Err(e) = sprout_manager.collect_orphans().await {
+            warn!("Collecting orphaned sprouts failed: {}", e);
+        }
+    }
+    info!("Orphaned sprout collection stopped.");
+
+    Ok(())
+}
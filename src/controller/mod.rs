@@ -1,5 +1,12 @@
 // Copyright 2025, Jeroen van Erp <jeroen@geeko.me>
 // SPDX-License-Identifier: Apache-2.0
+pub mod clusterseed;
 pub mod configmap;
+pub mod gc;
 pub mod namespace;
+mod retry;
 pub mod secret;
+mod seed;
+mod sprout;
+pub mod sproutpolicy;
+pub mod sproutrequest;
@@ -1,47 +1,232 @@
 // Copyright 2025, Jeroen van Erp <jeroen@geeko.me>
 // SPDX-License-Identifier: Apache-2.0
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
-use futures::{StreamExt, TryStreamExt};
+use futures::StreamExt;
 use k8s_openapi::api::core::v1::Namespace;
+use k8s_openapi::chrono::{DateTime, Utc};
 use kube::ResourceExt;
 use kube::{Api, Client};
+use kube_runtime::WatchStreamExt;
 use kube_runtime::watcher::{Config as WatcherConfig, Event, watcher};
-use tracing::info;
+use tracing::{info, warn};
 
+use crate::controller::retry::Retries;
+use crate::health::HEALTH;
+use crate::metrics::METRICS;
+use crate::shutdown::SHUTDOWN;
 use crate::sprout::manager::SproutManager;
 
+type Labels = BTreeMap<String, String>;
+
 pub async fn run(client: Client, sprout_manager: &SproutManager) -> anyhow::Result<()> {
     let ns_api: Api<Namespace> = Api::all(client.clone());
-    let mut watcher = watcher(ns_api, WatcherConfig::default()).boxed();
-    let mut seen = HashSet::new();
+    let mut watcher = watcher(ns_api, WatcherConfig::default())
+        .default_backoff()
+        .take_until(SHUTDOWN.wait())
+        .boxed();
+    let mut namespaces = Namespaces::new(sprout_manager.initialized_since());
+    let mut retries = Retries::default();
 
     info!("Starting Namespace watcher...");
-    while let Some(event) = watcher.try_next().await? {
+    HEALTH.watcher_started("Namespace");
+    loop {
+        let (ns, prune) = tokio::select! {
+            event = watcher.next() => match event {
+                Some(Ok(event)) => {
+                    HEALTH.watcher_progressed("Namespace");
+                    match namespaces.sync_of(event) {
+                        Some(sync) => sync,
+                        None => continue,
+                    }
+                }
+                Some(Err(e)) => {
+                    warn!("Namespace watcher failed, restarting with backoff: {}", e);
+                    METRICS.record_watcher_restart("Namespace");
+                    HEALTH.watcher_failed("Namespace");
+                    continue;
+                }
+                None => break,
+            },
+            sync = retries.next() => sync,
+        };
+        let ns_name = ns.name_any();
+        let result = if prune {
+            sprout_manager.namespace_changed(&ns).await
+        } else {
+            sprout_manager.new_namespace(&ns).await
+        };
+        match result {
+            Ok(()) => retries.succeeded(&ns_name),
+            Err(e) => {
+                METRICS.record_reconcile_error("Namespace");
+                let delay = retries.failed(&ns_name, (ns, prune));
+                warn!(
+                    "Handling Namespace '{}' failed, retrying in {:?}: {}",
+                    ns_name, delay, e
+                );
+            }
+        }
+    }
+
+    HEALTH.watcher_stopped("Namespace");
+    info!("Namespace watcher stopped.");
+    Ok(())
+}
+
+/// What the watcher knows of the namespaces, to tell which events require a sync.
+#[derive(Default)]
+struct Namespaces {
+    /// Labels and annotations of every active namespace, to detect changes that affect targeting.
+    seen: HashMap<String, (Labels, Labels)>,
+    /// The namespaces returned by the relist in progress.
+    relisted: HashSet<String>,
+    /// Whether the initial list completed; later lists are relists after the watch failed.
+    synced: bool,
+    /// Namespaces created from this moment on may be missed by `SproutManager::init`.
+    since: Option<DateTime<Utc>>,
+}
+
+impl Namespaces {
+    fn new(since: Option<DateTime<Utc>>) -> Self {
+        Self {
+            since,
+            ..Default::default()
+        }
+    }
+
+    /// The namespace to sync and whether to prune sprouts from it, if the event requires a sync.
+    fn sync_of(&mut self, event: Event<Namespace>) -> Option<(Namespace, bool)> {
         match event {
-            Event::Apply(ref ns) => {
-                let ns_name = ns.name_any();
-                match &ns.status {
-                    Some(status) if status.phase == Some("Active".to_string()) => {
-                        // Ensure the namespace is not already seen
-                        if seen.insert(ns_name.clone()) {
-                            info!("Namespace '{}' created or updated", ns_name);
-                            sprout_manager.new_namespace(&ns_name).await?;
-                        }
+            Event::Init => {
+                self.relisted.clear();
+                None
+            }
+            Event::InitApply(ns) if is_active(&ns) => {
+                self.relisted.insert(ns.name_any());
+                match self.seen.insert(ns.name_any(), metadata_of(&ns)) {
+                    Some(prev) if prev != metadata_of(&ns) => {
+                        info!("Namespace '{}' changed", ns.name_any());
+                        Some((ns, true))
+                    }
+                    Some(_) => None,
+                    // Namespaces present at start are sprouted by SproutManager::init, unless
+                    // they were created while it ran; any other is created while the watch was
+                    // down.
+                    None if self.synced || self.created_since(&ns) => {
+                        info!("Namespace '{}' created", ns.name_any());
+                        Some((ns, false))
+                    }
+                    None => None,
+                }
+            }
+            Event::InitDone => {
+                // Namespaces deleted while the watch was down are not relisted
+                let relisted = std::mem::take(&mut self.relisted);
+                self.seen.retain(|name, _| relisted.contains(name));
+                self.synced = true;
+                None
+            }
+            Event::Apply(ns) if is_active(&ns) => {
+                match self.seen.insert(ns.name_any(), metadata_of(&ns)) {
+                    None => {
+                        info!("Namespace '{}' created or updated", ns.name_any());
+                        Some((ns, false))
                     }
-                    _ => {
-                        continue;
+                    Some(prev) if prev != metadata_of(&ns) => {
+                        info!(
+                            "Namespace '{}' labels or annotations changed",
+                            ns.name_any()
+                        );
+                        Some((ns, true))
                     }
+                    _ => None,
                 }
             }
             Event::Delete(ns) => {
                 info!("Namespace '{}' deleted", ns.name_any());
-                seen.remove(&ns.name_any());
+                self.seen.remove(&ns.name_any());
+                None
             }
-            _ => {}
+            _ => None,
         }
     }
 
-    info!("Namespace watcher stopped.");
-    Ok(())
+    fn created_since(&self, ns: &Namespace) -> bool {
+        match (self.since, ns.creation_timestamp()) {
+            (Some(since), Some(created)) => created.0 >= since,
+            _ => false,
+        }
+    }
+}
+
+fn is_active(ns: &Namespace) -> bool {
+    ns.status
+        .as_ref()
+        .and_then(|s| s.phase.as_deref())
+        .map(|p| p == "Active")
+        .unwrap_or(false)
+}
+
+fn metadata_of(ns: &Namespace) -> (Labels, Labels) {
+    (ns.labels().clone(), ns.annotations().clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k8s_openapi::api::core::v1::NamespaceStatus;
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+    use std::time::Duration;
+
+    fn namespace(name: &str, created: DateTime<Utc>) -> Namespace {
+        let mut ns = Namespace::default();
+        ns.metadata.name = Some(name.to_string());
+        ns.metadata.creation_timestamp = Some(Time(created));
+        ns.status = Some(NamespaceStatus {
+            phase: Some("Active".to_string()),
+            ..Default::default()
+        });
+        ns
+    }
+
+    fn synced(namespaces: &mut Namespaces, listed: &[Namespace]) -> Vec<(String, bool)> {
+        let mut events = vec![Event::Init];
+        events.extend(listed.iter().cloned().map(Event::InitApply));
+        events.push(Event::InitDone);
+        events
+            .into_iter()
+            .filter_map(|e| namespaces.sync_of(e))
+            .map(|(ns, prune)| (ns.name_any(), prune))
+            .collect()
+    }
+
+    #[test]
+    fn test_initial_list_syncs_namespaces_created_since_init() {
+        let since = Utc::now();
+        let before = since - Duration::from_secs(60);
+        let mut namespaces = Namespaces::new(Some(since));
+        let listed = [namespace("old", before), namespace("new", since)];
+        assert_eq!(
+            synced(&mut namespaces, &listed),
+            vec![("new".to_string(), false)]
+        );
+    }
+
+    #[test]
+    fn test_relist_syncs_namespaces_created_and_forgets_those_deleted() {
+        let before = Utc::now() - Duration::from_secs(60);
+        let mut namespaces = Namespaces::new(None);
+        synced(
+            &mut namespaces,
+            &[namespace("ns-a", before), namespace("ns-b", before)],
+        );
+
+        let relisted = [namespace("ns-a", before), namespace("ns-c", before)];
+        assert_eq!(
+            synced(&mut namespaces, &relisted),
+            vec![("ns-c".to_string(), false)]
+        );
+        assert!(!namespaces.seen.contains_key("ns-b"));
+    }
 }
@@ -0,0 +1,125 @@
+// Copyright 2025, Jeroen van Erp <jeroen@geeko.me>
+// SPDX-License-Identifier: Apache-2.0
+use std::collections::HashMap;
+use std::time::Duration;
+
+use futures::StreamExt;
+use kube_runtime::watcher::Event;
+use tokio_util::time::{DelayQueue, delay_queue};
+
+use crate::utils::seed_ref;
+
+/// The delay before the first retry, doubled on every consecutive failure.
+const BASE_DELAY: Duration = Duration::from_secs(5);
+const MAX_DELAY: Duration = Duration::from_secs(300);
+
+/// Events that failed to be handled, waiting to be retried with exponential backoff. Keeps only
+/// the latest event and a single timer per object, and forgets both once a newer event was
+/// handled successfully.
+pub struct Retries<T> {
+    attempts: HashMap<String, u32>,
+    pending: HashMap<String, (T, delay_queue::Key)>,
+    timers: DelayQueue<String>,
+}
+
+impl<T> Default for Retries<T> {
+    fn default() -> Self {
+        Self {
+            attempts: HashMap::new(),
+            pending: HashMap::new(),
+            timers: DelayQueue::new(),
+        }
+    }
+}
+
+impl<T> Retries<T> {
+    /// Schedules the event for the object to be retried, and returns the delay until it is. A
+    /// retry that was already pending for the object is postponed by the new delay.
+    pub fn failed(&mut self, key: &str, event: T) -> Duration {
+        let attempts = self.attempts.entry(key.to_string()).or_default();
+        *attempts += 1;
+        let delay = backoff(*attempts);
+        let timer = match self.pending.remove(key) {
+            Some((_, timer)) => {
+                self.timers.reset(&timer, delay);
+                timer
+            }
+            None => self.timers.insert(key.to_string(), delay),
+        };
+        self.pending.insert(key.to_string(), (event, timer));
+        delay
+    }
+
+    /// Resets the backoff of the object, and drops its pending retry.
+    pub fn succeeded(&mut self, key: &str) {
+        self.attempts.remove(key);
+        if let Some((_, timer)) = self.pending.remove(key) {
+            self.timers.remove(&timer);
+        }
+    }
+
+    /// Waits for the next event that is due to be retried. Never completes if there is none.
+    pub async fn next(&mut self) -> T {
+        loop {
+            match self.timers.next().await {
+                Some(expired) => {
+                    if let Some((event, _)) = self.pending.remove(expired.get_ref()) {
+                        return event;
+                    }
+                }
+                None => std::future::pending().await,
+            }
+        }
+    }
+}
+
+/// The key an event is retried under, or `None` for events that concern no single object.
+pub fn key_of<K: kube::Resource>(event: &Event<K>) -> Option<String> {
+    match event {
+        Event::Apply(r) | Event::InitApply(r) | Event::Delete(r) => Some(seed_ref(r)),
+        Event::Init | Event::InitDone => None,
+    }
+}
+
+/// The delay before retrying after the given number of consecutive failures.
+pub fn backoff(attempts: u32) -> Duration {
+    BASE_DELAY
+        .saturating_mul(2u32.saturating_pow(attempts.saturating_sub(1)))
+        .min(MAX_DELAY)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff() {
+        assert_eq!(backoff(1), Duration::from_secs(5));
+        assert_eq!(backoff(2), Duration::from_secs(10));
+        assert_eq!(backoff(4), Duration::from_secs(40));
+        assert_eq!(backoff(7), MAX_DELAY);
+        assert_eq!(backoff(100), MAX_DELAY);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_retries_latest_event_until_succeeded() {
+        let start = tokio::time::Instant::now();
+        let mut retries = Retries::default();
+        assert_eq!(retries.failed("ns/a", 1), Duration::from_secs(5));
+        assert_eq!(retries.failed("ns/a", 2), Duration::from_secs(10));
+        retries.failed("ns/b", 3);
+        assert_eq!(retries.timers.len(), 2);
+
+        // The second failure of ns/a postponed its retry, which delivers its latest event
+        assert_eq!(retries.next().await, 3);
+        assert_eq!(start.elapsed(), Duration::from_secs(5));
+        assert_eq!(retries.next().await, 2);
+        assert_eq!(start.elapsed(), Duration::from_secs(10));
+
+        retries.failed("ns/c", 4);
+        retries.succeeded("ns/c");
+        assert!(retries.timers.is_empty());
+        let next = tokio::time::timeout(Duration::from_secs(60), retries.next());
+        assert!(next.await.is_err());
+    }
+}
@@ -0,0 +1,256 @@
+// Copyright 2025, Jeroen van Erp <jeroen@geeko.me>
+// SPDX-License-Identifier: Apache-2.0
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use futures::{StreamExt, TryStreamExt, future};
+use k8s_openapi::NamespaceResourceScope;
+use kube::core::PartialObjectMeta;
+use kube::{Api, Client, ResourceExt};
+use kube_runtime::controller::{Action, Config as ControllerConfig, Controller};
+use kube_runtime::watcher::{Config as WatcherConfig, Event, metadata_watcher};
+use kube_runtime::{WatchStreamExt, predicates, reflector};
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+use crate::controller::retry::{Retries, backoff};
+use crate::health::HEALTH;
+use crate::metrics::METRICS;
+use crate::shutdown::SHUTDOWN;
+use crate::sprout::kind::AsSproutKind;
+use crate::sprout::manager::SproutManager;
+use crate::utils::{has_finalizer, seed_ref};
+
+/// How long changes to the same object are collected before it is reconciled.
+const DEBOUNCE: Duration = Duration::from_secs(1);
+
+/// A reconciliation that failed, as the Controller requires a `std::error::Error`.
+#[derive(Debug)]
+pub struct ReconcileError(anyhow::Error);
+
+impl fmt::Display for ReconcileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#}", self.0)
+    }
+}
+
+impl std::error::Error for ReconcileError {}
+
+struct Context {
+    manager: Arc<SproutManager>,
+    /// The kind of the seeds, naming the watcher in logs, metrics and health.
+    watcher: &'static str,
+    resync_interval: Duration,
+    /// Consecutive failures by seed, to back off retries exponentially.
+    failures: Mutex<HashMap<String, u32>>,
+}
+
+/// Reconciles the seeds of kind `K` through a Controller, which deduplicates queued changes,
+/// reconciles a bounded number of seeds at once, retries failures with backoff and periodically
+/// resyncs every seed. Only the metadata of the objects is watched, as most of them are no seed;
+/// seeds are fetched in full when reconciled. Deleted seeds are not seen
+/// by the Controller, so their deletion is handled separately.
+pub async fn run<K>(
+    client: Client,
+    sprout_manager: Arc<SproutManager>,
+    watcher_name: &'static str,
+) -> anyhow::Result<()>
+where
+    K: kube::Resource<Scope = NamespaceResourceScope>
+        + Clone
+        + serde::de::DeserializeOwned
+        + serde::Serialize
+        + Sync
+        + Send
+        + std::fmt::Debug
+        + 'static
+        + AsSproutKind,
+    <K as kube::Resource>::DynamicType: Default + Clone + Eq + Hash + Unpin + std::fmt::Debug,
+{
+    let api: Api<K> = Api::all(client);
+    let (reader, writer) = reflector::store();
+    let (deleted_tx, deleted_rx) = mpsc::unbounded_channel();
+    let seeds = metadata_watcher(api, WatcherConfig::default())
+        .default_backoff()
+        // The store keeps only what deciding on a seed needs, not the bulky field managers
+        .modify(|meta| {
+            meta.managed_fields_mut().clear();
+            meta.annotations_mut()
+                .remove("kubectl.kubernetes.io/last-applied-configuration");
+        })
+        .reflect(writer)
+        .inspect(move |event| match event {
+            Ok(event) => {
+                HEALTH.watcher_progressed(watcher_name);
+                if let Event::Delete(seed) = event {
+                    // The receiver only goes away on shutdown
+                    let _ = deleted_tx.send(K::from_meta(seed.metadata.clone()));
+                }
+            }
+            Err(e) => {
+                warn!(
+                    "{} watcher failed, restarting with backoff: {}",
+                    watcher_name, e
+                );
+                METRICS.record_watcher_restart(watcher_name);
+                HEALTH.watcher_failed(watcher_name);
+            }
+        })
+        .applied_objects()
+        // A relisted watch repeats objects, and only a changed object has a new resource version
+        .predicate_filter(predicates::resource_version)
+        // Setting the finalizer changes the resource version too, but not the seed
+        .try_filter({
+            let sprout_manager = sprout_manager.clone();
+            move |seed| future::ready(!sprout_manager.is_own_change(seed))
+        })
+        .inspect_ok({
+            let sprout_manager = sprout_manager.clone();
+            move |seed| sprout_manager.change_observed(watcher_name, &seed_ref(seed))
+        });
+
+    let context = Arc::new(Context {
+        manager: sprout_manager.clone(),
+        watcher: watcher_name,
+        resync_interval: sprout_manager.config().resync_interval,
+        failures: Mutex::new(HashMap::new()),
+    });
+    let config = ControllerConfig::default()
+        .debounce(DEBOUNCE)
+        .concurrency(sprout_manager.config().seed_concurrency);
+    let controller = Controller::for_stream(seeds, reader)
+        .with_config(config)
+        .graceful_shutdown_on(SHUTDOWN.wait())
+        .run(
+            reconcile::<K>,
+            error_policy::<PartialObjectMeta<K>>,
+            context,
+        )
+        // Failures are handled by the error policy, and watch errors where they occur
+        .for_each(|_| async {});
+
+    info!("Starting {} controller...", watcher_name);
+    HEALTH.watcher_started(watcher_name);
+    tokio::join!(
+        controller,
+        delete_seeds::<K>(&sprout_manager, watcher_name, deleted_rx)
+    );
+    HEALTH.watcher_stopped(watcher_name);
+    info!("{} controller stopped.", watcher_name);
+
+    Ok(())
+}
+
+async fn reconcile<K>(
+    seed: Arc<PartialObjectMeta<K>>,
+    ctx: Arc<Context>,
+) -> Result<Action, ReconcileError>
+where
+    K: kube::Resource<Scope = NamespaceResourceScope>
+        + Clone
+        + serde::de::DeserializeOwned
+        + serde::Serialize
+        + Sync
+        + Send
+        + std::fmt::Debug
+        + 'static
+        + AsSproutKind,
+    <K as kube::Resource>::DynamicType: Default,
+{
+    let key = seed_ref(seed.as_ref());
+    let seed = K::from_meta(seed.metadata.clone());
+    let result = if ctx.manager.is_seed(&seed).await {
+        info!("{} '{}' changed, growing sprouts", ctx.watcher, key);
+        match ctx.manager.fetch_seed(&seed).await {
+            Ok(Some(seed)) => ctx
+                .manager
+                .resume_seed(seed)
+                .await
+                .map(|_| Action::requeue(ctx.resync_interval)),
+            // Deleted in the meantime, which the deletion of the seed handles
+            Ok(None) => Ok(Action::await_change()),
+            Err(e) => Err(e),
+        }
+    } else if has_finalizer(seed.meta()) || ctx.manager.is_known_seed(seed.clone()).await {
+        info!(
+            "{} '{}' is known, but no longer seed, deleting",
+            ctx.watcher, key
+        );
+        ctx.manager
+            .delete_seed(seed)
+            .await
+            .map(|_| Action::await_change())
+    } else {
+        Ok(Action::await_change())
+    };
+    if result.is_ok() {
+        ctx.failures.lock().unwrap().remove(&key);
+    }
+    result.map_err(ReconcileError)
+}
+
+fn error_policy<K>(seed: Arc<K>, err: &ReconcileError, ctx: Arc<Context>) -> Action
+where
+    K: kube::Resource,
+{
+    let key = seed_ref(seed.as_ref());
+    let mut failures = ctx.failures.lock().unwrap();
+    let attempts = failures.entry(key.clone()).or_default();
+    *attempts += 1;
+    let delay = backoff(*attempts);
+    METRICS.record_reconcile_error(ctx.watcher);
+    warn!(
+        "Reconciling {} '{}' failed, retrying in {:?}: {}",
+        ctx.watcher, key, delay, err
+    );
+    Action::requeue(delay)
+}
+
+/// Deletes the sprouts of deleted seeds, retrying failures with backoff.
+async fn delete_seeds<K>(
+    sprout_manager: &SproutManager,
+    watcher_name: &'static str,
+    mut deleted: mpsc::UnboundedReceiver<K>,
+) where
+    K: kube::Resource<Scope = NamespaceResourceScope>
+        + Clone
+        + serde::de::DeserializeOwned
+        + serde::Serialize
+        + Sync
+        + Send
+        + std::fmt::Debug
+        + 'static
+        + AsSproutKind,
+    <K as kube::Resource>::DynamicType: Default,
+{
+    let mut retries = Retries::default();
+    loop {
+        let seed = tokio::select! {
+            seed = deleted.recv() => match seed {
+                Some(seed) => seed,
+                None => break,
+            },
+            seed = retries.next() => seed,
+        };
+        let key = seed_ref(&seed);
+        if !sprout_manager.is_known_seed(seed.clone()).await {
+            retries.succeeded(&key);
+            continue;
+        }
+        info!("{} '{}' deleted", watcher_name, key);
+        match sprout_manager.delete_seed(seed.clone()).await {
+            Ok(()) => retries.succeeded(&key),
+            Err(e) => {
+                METRICS.record_reconcile_error(watcher_name);
+                let delay = retries.failed(&key, seed);
+                warn!(
+                    "Deleting sprouts of {} '{}' failed, retrying in {:?}: {}",
+                    watcher_name, key, delay, e
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,124 @@
+// Copyright 2025, Jeroen van Erp <jeroen@geeko.me>
+// SPDX-License-Identifier: Apache-2.0
+use std::collections::VecDeque;
+
+use k8s_openapi::NamespaceResourceScope;
+use kube_runtime::watcher::Event;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{info, warn};
+
+use crate::controller::retry::{Retries, key_of};
+use crate::health::HEALTH;
+use crate::kubernetes::cache::{Cache, CachedSprout};
+use crate::metrics::METRICS;
+use crate::shutdown::SHUTDOWN;
+use crate::sprout::kind::AsSproutKind;
+use crate::sprout::manager::SproutManager;
+
+/// Follows the changes to the sprouts of kind `K` in the cache, so sprouts changed or deleted by
+/// others are reverted, flagged or regrown right away instead of on the next resync of their
+/// seed.
+pub async fn run<K>(
+    cache: &Cache,
+    sprout_manager: &SproutManager,
+    watcher_name: &'static str,
+) -> anyhow::Result<()>
+where
+    K: kube::Resource<Scope = NamespaceResourceScope>
+        + Clone
+        + serde::de::DeserializeOwned
+        + serde::Serialize
+        + Sync
+        + Send
+        + std::fmt::Debug
+        + 'static
+        + AsSproutKind
+        + CachedSprout,
+    <K as kube::Resource>::DynamicType: Default,
+{
+    // Subscribing before reading the cache misses no change in between
+    let mut events = cache.sprout_events::<K>();
+    let mut retries = Retries::default();
+
+    info!("Starting {} watcher...", watcher_name);
+    HEALTH.watcher_started(watcher_name);
+    // The sprouts changed while no controller followed them are checked first
+    let mut backlog = cached::<K>(cache).await?;
+    HEALTH.watcher_progressed(watcher_name);
+    while !SHUTDOWN.is_triggered() {
+        let event = match backlog.pop_front() {
+            Some(event) => event,
+            None => tokio::select! {
+                event = events.recv() => match event {
+                    Ok(event) => event,
+                    Err(RecvError::Lagged(missed)) => {
+                        warn!(
+                            "{} watcher missed {} changes, checking every sprout",
+                            watcher_name, missed
+                        );
+                        METRICS.record_watcher_restart(watcher_name);
+                        backlog = cached::<K>(cache).await?;
+                        continue;
+                    }
+                    Err(RecvError::Closed) => break,
+                },
+                event = retries.next() => event,
+                _ = SHUTDOWN.wait() => break,
+            },
+        };
+        let Some(key) = key_of(&event) else {
+            continue;
+        };
+        match handle(sprout_manager, &event).await {
+            Ok(()) => retries.succeeded(&key),
+            Err(e) => {
+                METRICS.record_reconcile_error(watcher_name);
+                let delay = retries.failed(&key, event);
+                warn!(
+                    "Handling {} '{}' failed, retrying in {:?}: {}",
+                    watcher_name, key, delay, e
+                );
+            }
+        }
+    }
+    HEALTH.watcher_stopped(watcher_name);
+    info!("{} watcher stopped.", watcher_name);
+
+    Ok(())
+}
+
+/// The cached sprouts of kind `K`, as changes to check them all.
+async fn cached<K>(cache: &Cache) -> anyhow::Result<VecDeque<Event<K>>>
+where
+    K: CachedSprout,
+{
+    K::store(cache).wait_until_ready().await?;
+    Ok(cache
+        .sprouts::<K>()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|sprout| Event::Apply((*sprout).clone()))
+        .collect())
+}
+
+async fn handle<K>(sprout_manager: &SproutManager, event: &Event<K>) -> anyhow::Result<()>
+where
+    K: kube::Resource<Scope = NamespaceResourceScope>
+        + Clone
+        + serde::de::DeserializeOwned
+        + serde::Serialize
+        + Sync
+        + Send
+        + std::fmt::Debug
+        + 'static
+        + AsSproutKind,
+    <K as kube::Resource>::DynamicType: Default,
+{
+    match event {
+        Event::Apply(sprout) | Event::InitApply(sprout) => {
+            sprout_manager.sprout_changed(sprout.clone()).await
+        }
+        Event::Delete(sprout) => sprout_manager.sprout_deleted(sprout.clone()).await,
+        _ => Ok(()),
+    }
+}
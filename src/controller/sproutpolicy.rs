@@ -0,0 +1,79 @@
+// Copyright 2025, Jeroen van Erp <jeroen@geeko.me>
+// SPDX-License-Identifier: Apache-2.0
+use futures::StreamExt;
+use kube::ResourceExt;
+use kube::{Api, Client};
+use kube_runtime::WatchStreamExt;
+use kube_runtime::watcher::{Config as WatcherConfig, Event, watcher};
+
+use tracing::{info, warn};
+
+use crate::controller::retry::{Retries, key_of};
+use crate::health::HEALTH;
+use crate::metrics::METRICS;
+use crate::shutdown::SHUTDOWN;
+use crate::sprout::manager::SproutManager;
+use crate::types::SproutPolicy;
+
+pub async fn run(client: Client, sprout_manager: &SproutManager) -> anyhow::Result<()> {
+    let api: Api<SproutPolicy> = Api::all(client.clone());
+    let mut watcher = watcher(api, WatcherConfig::default())
+        .default_backoff()
+        .take_until(SHUTDOWN.wait())
+        .boxed();
+    let mut retries = Retries::default();
+
+    info!("Starting SproutPolicy watcher...");
+    HEALTH.watcher_started("SproutPolicy");
+    loop {
+        let event = tokio::select! {
+            event = watcher.next() => match event {
+                Some(Ok(event)) => {
+                    HEALTH.watcher_progressed("SproutPolicy");
+                    event
+                }
+                Some(Err(e)) => {
+                    warn!("SproutPolicy watcher failed, restarting with backoff: {}", e);
+                    METRICS.record_watcher_restart("SproutPolicy");
+                    HEALTH.watcher_failed("SproutPolicy");
+                    continue;
+                }
+                None => break,
+            },
+            event = retries.next() => event,
+        };
+        let Some(key) = key_of(&event) else {
+            continue;
+        };
+        match handle(sprout_manager, &event).await {
+            Ok(()) => retries.succeeded(&key),
+            Err(e) => {
+                METRICS.record_reconcile_error("SproutPolicy");
+                let delay = retries.failed(&key, event);
+                warn!(
+                    "Handling SproutPolicy '{}' failed, retrying in {:?}: {}",
+                    key, delay, e
+                );
+            }
+        }
+    }
+    HEALTH.watcher_stopped("SproutPolicy");
+    info!("SproutPolicy watcher stopped.");
+
+    Ok(())
+}
+
+async fn handle(sprout_manager: &SproutManager, event: &Event<SproutPolicy>) -> anyhow::Result<()> {
+    match event {
+        // Unchanged policies are ignored, so replaying the initial list is cheap
+        Event::Apply(policy) | Event::InitApply(policy) => {
+            sprout_manager.apply_policy(policy).await?;
+        }
+        Event::Delete(policy) => {
+            info!("SproutPolicy '{}' deleted", policy.name_any());
+            sprout_manager.delete_policy(policy).await?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
@@ -0,0 +1,92 @@
+// Copyright 2025, Jeroen van Erp <jeroen@geeko.me>
+// SPDX-License-Identifier: Apache-2.0
+use futures::StreamExt;
+use kube::ResourceExt;
+use kube::{Api, Client};
+use kube_runtime::WatchStreamExt;
+use kube_runtime::watcher::{Config as WatcherConfig, Event, watcher};
+
+use tracing::{info, warn};
+
+use crate::controller::retry::{Retries, key_of};
+use crate::health::HEALTH;
+use crate::metrics::METRICS;
+use crate::shutdown::SHUTDOWN;
+use crate::sprout::manager::SproutManager;
+use crate::types::SproutRequest;
+
+pub async fn run(client: Client, sprout_manager: &SproutManager) -> anyhow::Result<()> {
+    let api: Api<SproutRequest> = Api::all(client.clone());
+    let mut watcher = watcher(api, WatcherConfig::default())
+        .default_backoff()
+        .take_until(SHUTDOWN.wait())
+        .boxed();
+    let mut retries = Retries::default();
+
+    info!("Starting SproutRequest watcher...");
+    HEALTH.watcher_started("SproutRequest");
+    loop {
+        let event = tokio::select! {
+            event = watcher.next() => match event {
+                Some(Ok(event)) => {
+                    HEALTH.watcher_progressed("SproutRequest");
+                    event
+                }
+                Some(Err(e)) => {
+                    warn!("SproutRequest watcher failed, restarting with backoff: {}", e);
+                    METRICS.record_watcher_restart("SproutRequest");
+                    HEALTH.watcher_failed("SproutRequest");
+                    continue;
+                }
+                None => break,
+            },
+            event = retries.next() => event,
+        };
+        let Some(key) = key_of(&event) else {
+            continue;
+        };
+        match handle(sprout_manager, &event).await {
+            Ok(()) => retries.succeeded(&key),
+            Err(e) => {
+                METRICS.record_reconcile_error("SproutRequest");
+                let delay = retries.failed(&key, event);
+                warn!(
+                    "Handling SproutRequest '{}' failed, retrying in {:?}: {}",
+                    key, delay, e
+                );
+            }
+        }
+    }
+    HEALTH.watcher_stopped("SproutRequest");
+    info!("SproutRequest watcher stopped.");
+
+    Ok(())
+}
+
+async fn handle(
+    sprout_manager: &SproutManager,
+    event: &Event<SproutRequest>,
+) -> anyhow::Result<()> {
+    match event {
+        Event::Apply(req) | Event::InitApply(req) => {
+            info!(
+                "SproutRequest '{}/{}' for {} '{}' applied",
+                req.namespace().unwrap_or_default(),
+                req.name_any(),
+                req.spec.kind,
+                req.spec.seed
+            );
+            sprout_manager.request_sprout(req).await?;
+        }
+        Event::Delete(req) => {
+            info!(
+                "SproutRequest '{}/{}' deleted",
+                req.namespace().unwrap_or_default(),
+                req.name_any()
+            );
+            sprout_manager.withdraw_request(req).await?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
@@ -1,18 +1,38 @@
 // Copyright 2025, Jeroen van Erp <jeroen@geeko.me>
 // SPDX-License-Identifier: Apache-2.0
 use std::any::type_name_of_val;
+use std::collections::{BTreeSet, HashMap};
 
 use crate::{
+    kubernetes::events::{Reason, SproutEvent},
     kubernetes::manager::ResourceManager,
-    sprout::kind::AsSproutKind,
-    utils::{is_sprout, is_sprout_recent},
+    metrics::METRICS,
+    sprout::{kind::AsSproutKind, policy::Propagation},
+    types::{
+        DriftPolicy, SproutInventory, SproutReport, SproutRequest, SproutRequestStatus,
+        UpdateStrategy,
+    },
+    utils::{has_sprout_label, is_sprout_of, is_sprout_recent, seed_ref, sprout_hash, sprout_of},
 };
-use anyhow::Result;
+use anyhow::{Result, bail};
+use futures::{StreamExt, stream};
+use k8s_openapi::api::core::v1::Namespace;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+use k8s_openapi::chrono::Utc;
 use kube::api::ResourceExt;
 
 use tracing::{info, warn};
 
-pub async fn grow_sprouts<K, M>(resource: K, manager: &M) -> Result<()>
+/// Grows the sprouts of a seed into every namespace it targets or is requested in, and prunes
+/// those in namespaces it no longer targets. A failure to grow a single sprout does not stop the
+/// others from growing, but is recorded in the returned report, together with the number of
+/// sprouts that failed to grow or be pruned. Up to `concurrency` namespaces are handled at once.
+pub async fn grow_sprouts<K, M>(
+    resource: K,
+    manager: &M,
+    propagation: &Propagation,
+    concurrency: usize,
+) -> Result<(SproutReport, usize)>
 where
     K: kube::Resource<Scope = kube::core::NamespaceResourceScope>
         + Clone
@@ -28,60 +48,461 @@ where
 {
     let name = resource.name_any();
     let src_ns = resource.namespace().unwrap_or_default();
-    let namespaces = manager.list_namespaces().await?;
-    let hash = &resource.hash();
-    let res = crate::utils::create_sprout(resource, hash);
+    let seed = seed_ref(&resource);
+    let requests = requests_for::<K, M>(&seed, manager).await?;
+    let requestable = propagation.requestable;
+    let desired = desired_namespaces(&src_ns, &requests, propagation, manager).await?;
+    // The sprouts named like the seed, so existing sprouts can be reconciled without a GET each
+    let mut existing: HashMap<String, K> = manager
+        .list_by_name(&name)
+        .await?
+        .into_iter()
+        .map(|r| (r.namespace().unwrap_or_default(), r))
+        .collect();
+
+    let (res, hash) = propagation.sprout(resource);
+    let hash = &hash;
     let mut created = 0;
     let mut updated = 0;
+    let mut reverted = 0;
+    let mut drifted = 0;
     let mut ignored = 0;
     let mut validated = 0;
-    for target_ns in namespaces {
-        if target_ns == src_ns {
-            continue;
-        }
-
-        // Check if resource already exists in the target namespace
-        let pot_sprout = manager.get_in_namespace(&target_ns, &name).await?;
-        match pot_sprout {
-            Some(s) if is_sprout(s.meta()) && !is_sprout_recent(s.meta(), hash) => {
-                info!(
-                    "Updating sprout '{}/{}' of '{}/{}'",
-                    target_ns, name, src_ns, name
+    let mut failed = 0;
+    let mut pruned = 0;
+    let mut growths = HashMap::new();
+    let mut sprouts = Vec::new();
+    let targets: Vec<(String, Option<K>)> = desired
+        .into_iter()
+        .map(|ns| {
+            let current = existing.remove(&ns);
+            (ns, current)
+        })
+        .collect();
+    let res = &res;
+    let planted: Vec<_> = stream::iter(targets)
+        .map(|(target_ns, current)| async move {
+            // Not a known sprout, but there may be an object with the name that is no sprout
+            let current = match current {
+                Some(current) => Ok(Some(current)),
+                None => manager.get_in_namespace(&target_ns, &res.name_any()).await,
+            };
+            let current = match current {
+                Ok(current) => current,
+                Err(e) => return (target_ns, None, Err(e)),
+            };
+            let observed = current
+                .as_ref()
+                .and_then(|s| sprout_hash(s.meta()).cloned());
+            let growth = plant_sprout(res, hash, &target_ns, current, propagation, manager).await;
+            (target_ns, observed, growth)
+        })
+        .buffer_unordered(concurrency)
+        .collect()
+        .await;
+    for (target_ns, observed, growth) in planted {
+        match &growth {
+            Ok(Growth::Created) => created += 1,
+            Ok(Growth::Updated) => updated += 1,
+            Ok(Growth::Reverted) => reverted += 1,
+            Ok(Growth::Drifted) => drifted += 1,
+            Ok(Growth::Ignored) => ignored += 1,
+            Ok(Growth::Validated) => validated += 1,
+            Err(e) => {
+                warn!(
+                    "Growing sprout '{}/{}' of '{}' failed: {}",
+                    target_ns, name, seed, e
                 );
-                manager.update_in_namespace(&target_ns, &res).await?;
-                updated += 1;
+                failed += 1;
             }
-            Some(s) if is_sprout(s.meta()) => {
-                validated += 1;
+        }
+        sprouts.push(inventory(&seed, &target_ns, &growth, observed, hash));
+        growths.insert(target_ns, growth);
+    }
+
+    // Whatever is left is outside the desired set and must go if it was grown from this seed
+    let stale = existing
+        .into_iter()
+        .filter(|(target_ns, s)| *target_ns != src_ns && is_sprout_of(s.meta(), &seed))
+        .map(|(target_ns, _)| target_ns);
+    let (name, seed) = (&name, &seed);
+    let prunings: Vec<_> = stream::iter(stale)
+        .map(|target_ns| async move {
+            info!("Pruning sprout '{}/{}' of '{}'", target_ns, name, seed);
+            let result = manager.delete_from_namespace(&target_ns, name).await;
+            if result.is_ok() {
+                let note = "Pruned from a namespace the seed no longer targets";
+                let event = SproutEvent::new(Reason::SproutPruned, Some(&target_ns), note);
+                manager.publish_event(seed, &event).await;
             }
-            Some(s) => {
+            (target_ns, result)
+        })
+        .buffer_unordered(concurrency)
+        .collect()
+        .await;
+    for (target_ns, result) in prunings {
+        match result {
+            Ok(()) => pruned += 1,
+            Err(e) => {
                 warn!(
-                    "{} '{}/{}' exists but is no sprout",
-                    type_name_of_val(&s),
-                    target_ns,
-                    name
+                    "Pruning sprout '{}/{}' of '{}' failed: {}",
+                    target_ns, name, seed, e
                 );
-                ignored += 1;
+                METRICS.record_sprout_error(&target_ns);
+                failed += 1;
             }
-            None => {
+        }
+    }
+
+    for request in &requests {
+        let status = match growths.get(&request.namespace().unwrap_or_default()) {
+            Some(Err(e)) => SproutRequestStatus::failed(e.to_string()),
+            Some(Ok(growth)) => request_status(seed, requestable, Some(*growth), hash),
+            None => request_status(seed, requestable, None, hash),
+        };
+        if let Err(e) = update_request_status(request, status, manager).await {
+            warn!(
+                "Updating the status of SproutRequest '{}' failed: {}",
+                seed_ref(request),
+                e
+            );
+        }
+    }
+
+    METRICS.record_sprouts(K::sprout_kind(), "pruned", pruned);
+    info!(
+        "Growing sprouts of '{}' completed: {} created, {} updated, {} reverted, {} drifted, {} ignored, {} validated, {} failed, {} pruned",
+        seed, created, updated, reverted, drifted, ignored, validated, failed, pruned
+    );
+    Ok((SproutReport::new(hash.clone(), sprouts), failed))
+}
+
+/// The namespaces a seed in `src_ns` should have sprouts in: those it targets and, if it is
+/// requestable, those it is requested in.
+pub async fn desired_namespaces<K, M>(
+    src_ns: &str,
+    requests: &[SproutRequest],
+    propagation: &Propagation,
+    manager: &M,
+) -> Result<BTreeSet<String>>
+where
+    K: kube::Resource<Scope = kube::core::NamespaceResourceScope>
+        + Clone
+        + serde::de::DeserializeOwned
+        + serde::Serialize
+        + std::fmt::Debug
+        + Send
+        + Sync
+        + 'static
+        + AsSproutKind,
+    M: ResourceManager<K> + Sync,
+    <K as kube::Resource>::DynamicType: Default,
+{
+    let mut desired: BTreeSet<String> = manager
+        .list_namespaces()
+        .await?
+        .iter()
+        .filter(|ns| propagation.target.matches(ns))
+        .map(|ns| ns.name_any())
+        .collect();
+    if propagation.requestable {
+        desired.extend(requests.iter().filter_map(|r| r.namespace()));
+    }
+    desired.remove(src_ns);
+    Ok(desired)
+}
+
+/// Whether the seed should have a sprout in the namespace, because it targets the namespace or,
+/// if it is requestable, is requested there.
+pub async fn is_desired_in<K, M>(
+    seed: &str,
+    ns: &Namespace,
+    propagation: &Propagation,
+    manager: &M,
+) -> Result<bool>
+where
+    K: kube::Resource<Scope = kube::core::NamespaceResourceScope>
+        + Clone
+        + serde::de::DeserializeOwned
+        + serde::Serialize
+        + std::fmt::Debug
+        + Send
+        + Sync
+        + 'static
+        + AsSproutKind,
+    M: ResourceManager<K> + Sync,
+    <K as kube::Resource>::DynamicType: Default,
+{
+    if propagation.target.matches(ns) {
+        return Ok(true);
+    }
+    if !propagation.requestable {
+        return Ok(false);
+    }
+    let namespace = ns.name_any();
+    Ok(requests_for::<K, M>(seed, manager)
+        .await?
+        .iter()
+        .any(|r| r.namespace().as_deref() == Some(namespace.as_str())))
+}
+
+/// The outcome of growing a single sprout in a namespace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Growth {
+    Created,
+    Updated,
+    /// The sprout was changed, and restored to match the seed.
+    Reverted,
+    /// The sprout was changed and no longer matches the seed, but is left as it is.
+    Drifted,
+    Ignored,
+    Validated,
+}
+
+/// Ensures `sprout` (as produced by `Propagation::sprout`) is present in `target_ns`, and up to
+/// date unless the update strategy says otherwise. `current` is the object in `target_ns` as the
+/// caller last saw it, if any. Returns the outcome together with the resulting inventory entry.
+pub async fn grow_sprout<K, M>(
+    sprout: &K,
+    hash: &Option<String>,
+    target_ns: &str,
+    current: Option<K>,
+    propagation: &Propagation,
+    manager: &M,
+) -> Result<(Growth, SproutInventory)>
+where
+    K: kube::Resource<Scope = kube::core::NamespaceResourceScope>
+        + Clone
+        + serde::de::DeserializeOwned
+        + serde::Serialize
+        + std::fmt::Debug
+        + Send
+        + Sync
+        + 'static
+        + AsSproutKind,
+    M: ResourceManager<K> + Sync,
+    <K as kube::Resource>::DynamicType: Default,
+{
+    let observed = current
+        .as_ref()
+        .and_then(|s| sprout_hash(s.meta()).cloned());
+    let growth = plant_sprout(sprout, hash, target_ns, current, propagation, manager).await?;
+    let seed = sprout_of(sprout.meta()).cloned().unwrap_or_default();
+    let entry = inventory(&seed, target_ns, &Ok(growth), observed, hash);
+    Ok((growth, entry))
+}
+
+/// The inventory entry of the sprout in `target_ns`, given the outcome of growing it and the hash
+/// of the sprout that was there before.
+fn inventory(
+    seed: &str,
+    target_ns: &str,
+    growth: &Result<Growth>,
+    observed: Option<String>,
+    hash: &Option<String>,
+) -> SproutInventory {
+    let (observed_hash, error) = match growth {
+        Ok(Growth::Created | Growth::Updated | Growth::Reverted) => (hash.clone(), None),
+        Ok(Growth::Validated) => (observed, None),
+        Ok(Growth::Drifted) => (
+            observed,
+            Some("The sprout was changed and no longer matches the seed".to_string()),
+        ),
+        Ok(Growth::Ignored) => (
+            None,
+            Some(format!(
+                "An object with this name exists but is no sprout of '{}'",
+                seed
+            )),
+        ),
+        Err(e) => (observed, Some(e.to_string())),
+    };
+    SproutInventory {
+        namespace: target_ns.to_string(),
+        ready: error.is_none() && observed_hash == *hash,
+        observed_hash,
+        last_sync: Time(Utc::now()),
+        error,
+    }
+}
+
+/// Creates or updates the sprout in `target_ns`, given the object that is there. A sprout grown
+/// from the current seed data whose own data differs was changed by someone else: it drifted.
+async fn plant_sprout<K, M>(
+    sprout: &K,
+    hash: &Option<String>,
+    target_ns: &str,
+    current: Option<K>,
+    propagation: &Propagation,
+    manager: &M,
+) -> Result<Growth>
+where
+    K: kube::Resource<Scope = kube::core::NamespaceResourceScope>
+        + Clone
+        + serde::de::DeserializeOwned
+        + serde::Serialize
+        + std::fmt::Debug
+        + Send
+        + Sync
+        + 'static
+        + AsSproutKind,
+    M: ResourceManager<K> + Sync,
+    <K as kube::Resource>::DynamicType: Default,
+{
+    let name = sprout.name_any();
+    let seed = sprout_of(sprout.meta()).cloned().unwrap_or_default();
+    // Existing sprouts are only ever touched if the seed says so
+    let updatable = propagation.strategy == UpdateStrategy::Always;
+    let (growth, result) = match current {
+        Some(s) if is_sprout_of(s.meta(), &seed) => {
+            if !(is_sprout_recent(s.meta(), hash) && has_sprout_label(s.meta())) {
+                if !updatable {
+                    return Ok(Growth::Validated);
+                }
+                info!("Updating sprout '{}/{}' of '{}'", target_ns, name, seed);
+                let result = manager.update_in_namespace(target_ns, sprout).await;
+                (Growth::Updated, result)
+            } else if s.hash() == sprout.hash() {
+                return Ok(Growth::Validated);
+            } else if updatable && propagation.drift == DriftPolicy::Revert {
                 info!(
-                    "Creating sprout '{}/{}' of '{}/{}'",
-                    target_ns, name, src_ns, name
+                    "Reverting drifted sprout '{}/{}' of '{}'",
+                    target_ns, name, seed
+                );
+                let result = manager.update_in_namespace(target_ns, sprout).await;
+                (Growth::Reverted, result)
+            } else {
+                warn!(
+                    "Sprout '{}/{}' of '{}' was changed and no longer matches it",
+                    target_ns, name, seed
                 );
-                manager.create_in_namespace(&target_ns, &res).await?;
-                created += 1;
+                (Growth::Drifted, Ok(()))
+            }
+        }
+        Some(s) => {
+            warn!(
+                "{} '{}/{}' exists but is no sprout of '{}'",
+                type_name_of_val(&s),
+                target_ns,
+                name,
+                seed
+            );
+            (Growth::Ignored, Ok(()))
+        }
+        None => {
+            info!("Creating sprout '{}/{}' of '{}'", target_ns, name, seed);
+            let result = manager.create_in_namespace(target_ns, sprout).await;
+            (Growth::Created, result)
+        }
+    };
+    let (reason, note) = match (&result, growth) {
+        (Ok(_), Growth::Created) => (Reason::SproutCreated, "Sprout created".to_string()),
+        (Ok(_), Growth::Updated) => (Reason::SproutUpdated, "Sprout updated".to_string()),
+        (Ok(_), Growth::Reverted) => (
+            Reason::SproutReverted,
+            "Sprout was changed and is reverted to match the seed".to_string(),
+        ),
+        (Ok(_), Growth::Drifted) => (
+            Reason::SproutDrifted,
+            "Sprout was changed and no longer matches the seed".to_string(),
+        ),
+        (Ok(_), _) => (
+            Reason::SproutConflict,
+            "An object with this name exists but is no sprout of the seed".to_string(),
+        ),
+        (Err(e), Growth::Updated | Growth::Reverted) => (Reason::SproutUpdateFailed, e.to_string()),
+        (Err(e), _) => (Reason::SproutCreateFailed, e.to_string()),
+    };
+    let event = SproutEvent::new(reason, Some(target_ns), note);
+    let new = manager.publish_event(&seed, &event).await;
+    match &result {
+        // A lasting state such as a drifted sprout is counted once, like its Event is recorded
+        Ok(_) if new => {
+            let action = match growth {
+                Growth::Created => "created",
+                Growth::Updated => "updated",
+                Growth::Reverted => "reverted",
+                Growth::Drifted => "drifted",
+                _ => "ignored",
+            };
+            METRICS.record_sprouts(K::sprout_kind(), action, 1);
+        }
+        Ok(_) => {}
+        Err(_) => METRICS.record_sprout_error(target_ns),
+    }
+    result.map(|_| growth)
+}
+
+/// Deletes the sprout named `name` from `target_ns` if it was grown from `seed`.
+pub async fn prune_sprout<K, M>(
+    seed: &str,
+    name: &str,
+    target_ns: &str,
+    manager: &M,
+) -> Result<bool>
+where
+    K: kube::Resource<Scope = kube::core::NamespaceResourceScope>
+        + Clone
+        + serde::de::DeserializeOwned
+        + serde::Serialize
+        + std::fmt::Debug
+        + Send
+        + Sync
+        + 'static
+        + AsSproutKind,
+    M: ResourceManager<K> + Sync,
+    <K as kube::Resource>::DynamicType: Default,
+{
+    match manager.get_in_namespace(target_ns, name).await? {
+        Some(s) if is_sprout_of(s.meta(), seed) => {
+            info!("Pruning sprout '{}/{}' of '{}'", target_ns, name, seed);
+            if let Err(e) = manager.delete_from_namespace(target_ns, name).await {
+                METRICS.record_sprout_error(target_ns);
+                return Err(e);
             }
+            let note = "Pruned from a namespace the seed no longer targets";
+            let event = SproutEvent::new(Reason::SproutPruned, Some(target_ns), note);
+            manager.publish_event(seed, &event).await;
+            METRICS.record_sprouts(K::sprout_kind(), "pruned", 1);
+            Ok(true)
         }
+        _ => Ok(false),
     }
+}
 
-    info!(
-        "Growing sprouts of '{}/{}' completed: {} created, {} updated, {} ignored, {} validated",
-        src_ns, name, created, updated, ignored, validated
-    );
-    Ok(())
+/// The status of a SproutRequest for a seed, given the outcome of growing its sprout.
+pub fn request_status(
+    seed: &str,
+    requestable: bool,
+    growth: Option<Growth>,
+    hash: &Option<String>,
+) -> SproutRequestStatus {
+    match growth {
+        _ if !requestable => {
+            SproutRequestStatus::failed(format!("Seed '{}' is not requestable", seed))
+        }
+        Some(Growth::Ignored) => SproutRequestStatus::failed(format!(
+            "An object with the name of seed '{}' exists but is no sprout of it",
+            seed
+        )),
+        Some(Growth::Drifted) => SproutRequestStatus::failed(format!(
+            "The sprout of seed '{}' was changed and no longer matches it",
+            seed
+        )),
+        Some(_) => SproutRequestStatus::ready(hash.clone()),
+        None => SproutRequestStatus::failed(format!(
+            "Seed '{}' cannot be sprouted into its own namespace",
+            seed
+        )),
+    }
 }
 
-pub async fn delete_sprouts<K, M>(resource: K, manager: &M) -> Result<()>
+/// Updates the status of a SproutRequest, unless it is already up to date.
+pub async fn update_request_status<K, M>(
+    request: &SproutRequest,
+    status: SproutRequestStatus,
+    manager: &M,
+) -> Result<()>
 where
     K: kube::Resource<Scope = kube::core::NamespaceResourceScope>
         + Clone
@@ -93,45 +514,708 @@ where
         + 'static,
     M: ResourceManager<K> + Sync,
     <K as kube::Resource>::DynamicType: Default,
+{
+    if request.status.as_ref() != Some(&status) {
+        manager.update_request_status(request, &status).await?;
+    }
+    Ok(())
+}
+
+/// The SproutRequests for the seed, of its kind.
+pub async fn requests_for<K, M>(seed: &str, manager: &M) -> Result<Vec<SproutRequest>>
+where
+    K: kube::Resource<Scope = kube::core::NamespaceResourceScope>
+        + Clone
+        + serde::de::DeserializeOwned
+        + serde::Serialize
+        + std::fmt::Debug
+        + Send
+        + Sync
+        + 'static
+        + AsSproutKind,
+    M: ResourceManager<K> + Sync,
+    <K as kube::Resource>::DynamicType: Default,
+{
+    Ok(manager
+        .list_requests(seed)
+        .await?
+        .into_iter()
+        .filter(|r| r.spec.kind == K::sprout_kind())
+        .collect())
+}
+
+/// Deletes every sprout of the seed, wherever it grew, up to `concurrency` at once.
+pub async fn delete_sprouts<K, M>(resource: K, manager: &M, concurrency: usize) -> Result<()>
+where
+    K: kube::Resource<Scope = kube::core::NamespaceResourceScope>
+        + Clone
+        + serde::de::DeserializeOwned
+        + serde::Serialize
+        + std::fmt::Debug
+        + Send
+        + Sync
+        + 'static
+        + AsSproutKind,
+    M: ResourceManager<K> + Sync,
+    <K as kube::Resource>::DynamicType: Default,
 {
     let name = resource.name_any();
     let src_ns = resource.namespace().unwrap_or_default();
-    let namespaces = manager.list_namespaces().await?;
+    let seed = seed_ref(&resource);
     let mut deleted = 0;
     let mut ignored = 0;
-    for target_ns in namespaces {
+    let mut failed = 0;
+    let mut sprouts = Vec::new();
+    for s in manager.list_by_name(&name).await? {
+        let target_ns = s.namespace().unwrap_or_default();
         if target_ns == src_ns {
             continue;
         }
 
-        // Check if the sprout exists in the target namespace
-        let pot_sprout = manager.get_in_namespace(&target_ns, &name).await?;
-        match pot_sprout {
-            Some(sprout) if is_sprout(sprout.meta()) => {
-                info!(
-                    "Deleting sprout '{}/{}' of '{}/{}'",
-                    target_ns, name, src_ns, name
-                );
-                manager.delete_from_namespace(&target_ns, &name).await?;
-                deleted += 1;
+        if is_sprout_of(s.meta(), &seed) {
+            sprouts.push(target_ns);
+        } else {
+            warn!(
+                "{} '{}/{}' exists but is no sprout of '{}'",
+                type_name_of_val(&s),
+                target_ns,
+                name,
+                seed
+            );
+            ignored += 1;
+        }
+    }
+    let (name, seed) = (&name, &seed);
+    let deletions: Vec<_> = stream::iter(sprouts)
+        .map(|target_ns| async move {
+            info!("Deleting sprout '{}/{}' of '{}'", target_ns, name, seed);
+            let result = manager.delete_from_namespace(&target_ns, name).await;
+            if result.is_ok() {
+                let note = "Deleted as the seed is no longer available";
+                let event = SproutEvent::new(Reason::SproutDeleted, Some(&target_ns), note);
+                manager.publish_event(seed, &event).await;
             }
-            Some(s) => {
+            (target_ns, result)
+        })
+        .buffer_unordered(concurrency)
+        .collect()
+        .await;
+    for (target_ns, result) in deletions {
+        match result {
+            Ok(()) => deleted += 1,
+            Err(e) => {
                 warn!(
-                    "{} '{}/{}' exists but is no sprout",
-                    type_name_of_val(&s),
-                    target_ns,
-                    name
+                    "Deleting sprout '{}/{}' of '{}' failed: {}",
+                    target_ns, name, seed, e
                 );
-                ignored += 1;
-            }
-            _ => {
-                continue;
+                METRICS.record_sprout_error(&target_ns);
+                failed += 1;
             }
         }
     }
+    for request in requests_for::<K, M>(seed, manager).await? {
+        let status = SproutRequestStatus::failed(format!("Seed '{}' is no longer available", seed));
+        if let Err(e) = update_request_status(&request, status, manager).await {
+            warn!(
+                "Updating the status of SproutRequest '{}' failed: {}",
+                seed_ref(&request),
+                e
+            );
+        }
+    }
+    METRICS.record_sprouts(K::sprout_kind(), "deleted", deleted);
     info!(
-        "Deleting sprouts of '{}/{}' completed: {} deleted, {} ignored",
-        src_ns, name, deleted, ignored
+        "Deleting sprouts of '{}' completed: {} deleted, {} ignored, {} failed",
+        seed, deleted, ignored, failed
     );
+    if failed > 0 {
+        bail!("{} sprouts of '{}' could not be deleted", failed, seed);
+    }
     Ok(())
 }
+
+/// Deletes the sprouts of a seed that no longer exists or is no longer a seed, or only reports
+/// them when `dry_run` is set. Returns the number of orphaned sprouts that are left.
+pub async fn delete_orphans<K, M>(seed: &str, orphans: &[K], dry_run: bool, manager: &M) -> usize
+where
+    K: kube::Resource<Scope = kube::core::NamespaceResourceScope>
+        + Clone
+        + serde::de::DeserializeOwned
+        + serde::Serialize
+        + std::fmt::Debug
+        + Send
+        + Sync
+        + 'static
+        + AsSproutKind,
+    M: ResourceManager<K> + Sync,
+    <K as kube::Resource>::DynamicType: Default,
+{
+    let mut deleted = 0;
+    let mut left = 0;
+    for orphan in orphans {
+        let target_ns = orphan.namespace().unwrap_or_default();
+        let name = orphan.name_any();
+        if dry_run {
+            warn!(
+                "Sprout '{}/{}' of '{}' is orphaned, leaving it in dry-run",
+                target_ns, name, seed
+            );
+            left += 1;
+            continue;
+        }
+        info!(
+            "Deleting orphaned sprout '{}/{}' of '{}'",
+            target_ns, name, seed
+        );
+        match manager.delete_from_namespace(&target_ns, &name).await {
+            Ok(()) => {
+                let note = "Deleted as the seed is no longer available";
+                let event = SproutEvent::new(Reason::SproutDeleted, Some(&target_ns), note);
+                manager.publish_event(seed, &event).await;
+                deleted += 1;
+            }
+            Err(e) => {
+                warn!(
+                    "Deleting orphaned sprout '{}/{}' of '{}' failed: {}",
+                    target_ns, name, seed, e
+                );
+                METRICS.record_sprout_error(&target_ns);
+                left += 1;
+            }
+        }
+    }
+    METRICS.record_sprouts(K::sprout_kind(), "deleted", deleted);
+    left
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::kubernetes::manager::MockResourceManager;
+    use crate::sprout::kind::SproutKind;
+    use crate::types::{ClusterSeed, ClusterSeedSpec, SproutRequestSpec};
+    use crate::utils::{ANNOTATION_KEY, NAMESPACE_SELECTOR_KEY, REQUESTABLE_KEY, TARGETS_KEY};
+    use k8s_openapi::api::core::v1::{ConfigMap, Namespace};
+    use kube::Resource;
+    use std::collections::BTreeMap;
+
+    fn namespace(name: &str, labels: &[(&str, &str)]) -> Namespace {
+        let mut ns = Namespace::default();
+        ns.metadata.name = Some(name.to_string());
+        ns.metadata.labels = Some(
+            labels
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        );
+        ns
+    }
+
+    fn seed(annotations: &[(&str, &str)]) -> ConfigMap {
+        let mut cm = ConfigMap::default();
+        cm.metadata.name = Some("shared".to_string());
+        cm.metadata.namespace = Some("source".to_string());
+        cm.metadata.annotations = Some(
+            [(ANNOTATION_KEY, "true")]
+                .iter()
+                .chain(annotations)
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        );
+        cm.data = Some(BTreeMap::from([("key".to_string(), "value".to_string())]));
+        cm
+    }
+
+    fn propagation(cm: &ConfigMap) -> Propagation {
+        Propagation::from_annotations(cm.meta(), SproutKind::ConfigMap, &Config::default()).unwrap()
+    }
+
+    fn sprout_in(ns: &str, of: &ConfigMap) -> ConfigMap {
+        let mut sprout = crate::utils::create_sprout(of.clone(), &of.hash());
+        sprout.metadata.namespace = Some(ns.to_string());
+        sprout
+    }
+
+    fn request(ns: &str, kind: SproutKind) -> SproutRequest {
+        let mut req = SproutRequest::new(
+            "shared",
+            SproutRequestSpec {
+                seed: "source/shared".to_string(),
+                kind,
+            },
+        );
+        req.metadata.namespace = Some(ns.to_string());
+        req
+    }
+
+    fn plain_in(ns: &str) -> ConfigMap {
+        let mut cm = ConfigMap::default();
+        cm.metadata.name = Some("shared".to_string());
+        cm.metadata.namespace = Some(ns.to_string());
+        cm
+    }
+
+    #[tokio::test]
+    async fn test_grow_sprouts_only_in_selected_namespaces() {
+        let mut mgr = MockResourceManager::<ConfigMap>::new();
+        mgr.expect_publish_event().return_const(true);
+        mgr.expect_list_namespaces().returning(|| {
+            Ok(vec![
+                namespace("source", &[("team", "payments")]),
+                namespace("payments", &[("team", "payments")]),
+                namespace("billing", &[("team", "billing")]),
+            ])
+        });
+        mgr.expect_list_by_name().returning(|_| Ok(vec![]));
+        mgr.expect_get_in_namespace().returning(|_, _| Ok(None));
+        mgr.expect_list_requests().returning(|_| Ok(vec![]));
+        mgr.expect_create_in_namespace()
+            .withf(|ns, _| ns == "payments")
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let cm = seed(&[(NAMESPACE_SELECTOR_KEY, "team=payments")]);
+        grow_sprouts(cm.clone(), &mgr, &propagation(&cm), 4)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_grow_sprouts_create_only_leaves_stale_sprouts() {
+        let cm = seed(&[]);
+        let mut mgr = MockResourceManager::<ConfigMap>::new();
+        mgr.expect_publish_event().return_const(true);
+        mgr.expect_list_namespaces()
+            .returning(|| Ok(vec![namespace("ns-a", &[]), namespace("ns-b", &[])]));
+        let current = cm.clone();
+        mgr.expect_list_by_name().returning(move |_| {
+            let mut stale = sprout_in("ns-a", &current);
+            stale
+                .annotations_mut()
+                .insert("sprouter.geeko.me/seed-hash".to_string(), "old".to_string());
+            Ok(vec![stale])
+        });
+        mgr.expect_get_in_namespace().returning(|_, _| Ok(None));
+        mgr.expect_list_requests().returning(|_| Ok(vec![]));
+        mgr.expect_update_in_namespace().never();
+        mgr.expect_create_in_namespace()
+            .withf(|ns, _| ns == "ns-b")
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let propagation = Propagation {
+            strategy: UpdateStrategy::CreateOnly,
+            ..propagation(&cm)
+        };
+        grow_sprouts(cm, &mgr, &propagation, 4).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_grow_sprouts_reports_every_namespace() {
+        let cm = seed(&[]);
+        let mut mgr = MockResourceManager::<ConfigMap>::new();
+        mgr.expect_publish_event().return_const(true);
+        mgr.expect_list_namespaces().returning(|| {
+            Ok(vec![
+                namespace("ns-a", &[]),
+                namespace("ns-b", &[]),
+                namespace("ns-c", &[]),
+            ])
+        });
+        // Objects that are no sprout are not listed, but looked up
+        mgr.expect_list_by_name().returning(|_| Ok(vec![]));
+        mgr.expect_get_in_namespace()
+            .returning(|ns, _| Ok((ns == "ns-c").then(|| plain_in("ns-c"))));
+        mgr.expect_list_requests().returning(|_| Ok(vec![]));
+        mgr.expect_create_in_namespace()
+            .withf(|ns, _| ns == "ns-a")
+            .returning(|_, _| Err(anyhow::anyhow!("forbidden")));
+        mgr.expect_create_in_namespace()
+            .withf(|ns, _| ns == "ns-b")
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let (report, failed) = grow_sprouts(cm.clone(), &mgr, &propagation(&cm), 4)
+            .await
+            .unwrap();
+        assert_eq!(failed, 1);
+        assert_eq!(report.ready, "1/3");
+        assert_eq!(report.seed_hash, cm.hash());
+        let errors: Vec<_> = report
+            .sprouts
+            .iter()
+            .map(|s| (s.namespace.as_str(), s.ready, s.error.is_some()))
+            .collect();
+        assert_eq!(
+            errors,
+            vec![
+                ("ns-a", false, true),
+                ("ns-b", true, false),
+                ("ns-c", false, true)
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_grow_sprouts_labels_sprouts_of_older_versions() {
+        let cm = seed(&[]);
+        let mut mgr = MockResourceManager::<ConfigMap>::new();
+        mgr.expect_publish_event().return_const(true);
+        mgr.expect_list_namespaces()
+            .returning(|| Ok(vec![namespace("ns-a", &[])]));
+        mgr.expect_list_by_name().returning(|_| Ok(vec![]));
+        let current = cm.clone();
+        mgr.expect_get_in_namespace().returning(move |_, _| {
+            let mut unlabelled = sprout_in("ns-a", &current);
+            unlabelled.labels_mut().clear();
+            Ok(Some(unlabelled))
+        });
+        mgr.expect_list_requests().returning(|_| Ok(vec![]));
+        mgr.expect_update_in_namespace()
+            .withf(|ns, s| ns == "ns-a" && has_sprout_label(s.meta()))
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        grow_sprouts(cm.clone(), &mgr, &propagation(&cm), 4)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_grow_sprout_does_not_look_up_the_sprout() {
+        let cm = seed(&[]);
+        let mut mgr = MockResourceManager::<ConfigMap>::new();
+        mgr.expect_publish_event().return_const(true);
+        mgr.expect_get_in_namespace().never();
+        mgr.expect_create_in_namespace()
+            .withf(|ns, _| ns == "ns-a")
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let propagation = propagation(&cm);
+        let (sprout, h) = propagation.sprout(cm);
+        let (growth, entry) = grow_sprout(&sprout, &h, "ns-a", None, &propagation, &mgr)
+            .await
+            .unwrap();
+        assert_eq!(growth, Growth::Created);
+        assert!(entry.ready);
+    }
+
+    #[rstest::rstest]
+    #[case(DriftPolicy::Revert, Reason::SproutReverted, 1)]
+    #[case(DriftPolicy::Flag, Reason::SproutDrifted, 0)]
+    #[tokio::test]
+    async fn test_grow_sprouts_handles_drifted_sprouts(
+        #[case] drift: DriftPolicy,
+        #[case] reason: Reason,
+        #[case] updates: usize,
+    ) {
+        let cm = seed(&[]);
+        let mut mgr = MockResourceManager::<ConfigMap>::new();
+        mgr.expect_list_namespaces()
+            .returning(|| Ok(vec![namespace("ns-a", &[])]));
+        let current = cm.clone();
+        mgr.expect_list_by_name().returning(move |_| {
+            let mut edited = sprout_in("ns-a", &current);
+            edited.data = Some(BTreeMap::from([("key".to_string(), "edited".to_string())]));
+            Ok(vec![edited])
+        });
+        mgr.expect_list_requests().returning(|_| Ok(vec![]));
+        mgr.expect_update_in_namespace()
+            .withf(|ns, s| ns == "ns-a" && s.data == seed(&[]).data)
+            .times(updates)
+            .returning(|_, _| Ok(()));
+        mgr.expect_publish_event()
+            .withf(move |_, e| e.reason == reason)
+            .times(1)
+            .return_const(true);
+
+        let propagation = Propagation {
+            drift,
+            ..propagation(&cm)
+        };
+        let (report, failed) = grow_sprouts(cm, &mgr, &propagation, 4).await.unwrap();
+        assert_eq!(failed, 0);
+        assert_eq!(report.ready, format!("{}/1", updates));
+    }
+
+    #[rstest::rstest]
+    #[case(false, 1, 0)]
+    #[case(true, 0, 1)]
+    #[tokio::test]
+    async fn test_delete_orphans(
+        #[case] dry_run: bool,
+        #[case] deletions: usize,
+        #[case] left: usize,
+    ) {
+        let cm = seed(&[]);
+        let mut mgr = MockResourceManager::<ConfigMap>::new();
+        mgr.expect_publish_event().return_const(true);
+        mgr.expect_delete_from_namespace()
+            .withf(|ns, name| ns == "ns-a" && name == "shared")
+            .times(deletions)
+            .returning(|_, _| Ok(()));
+
+        let orphans = [sprout_in("ns-a", &cm)];
+        assert_eq!(
+            delete_orphans("source/shared", &orphans, dry_run, &mgr).await,
+            left
+        );
+    }
+
+    #[tokio::test]
+    async fn test_grow_sprouts_reconciles_targets() {
+        let cm = seed(&[(TARGETS_KEY, "ns-a,ns-b")]);
+        let mut mgr = MockResourceManager::<ConfigMap>::new();
+        mgr.expect_list_namespaces().returning(|| {
+            Ok(vec![
+                namespace("source", &[]),
+                namespace("ns-a", &[]),
+                namespace("ns-b", &[]),
+                namespace("ns-c", &[]),
+                namespace("ns-d", &[]),
+            ])
+        });
+        let current = cm.clone();
+        mgr.expect_list_by_name().returning(move |_| {
+            let mut stale = sprout_in("ns-b", &current);
+            stale.data = Some(BTreeMap::new());
+            stale
+                .annotations_mut()
+                .insert("sprouter.geeko.me/seed-hash".to_string(), "old".to_string());
+            Ok(vec![
+                current.clone(),
+                sprout_in("ns-a", &current),
+                stale,
+                sprout_in("ns-c", &current),
+                plain_in("ns-d"),
+            ])
+        });
+        mgr.expect_list_requests().returning(|_| Ok(vec![]));
+        mgr.expect_create_in_namespace().never();
+        mgr.expect_update_in_namespace()
+            .withf(|ns, _| ns == "ns-b")
+            .times(1)
+            .returning(|_, _| Ok(()));
+        mgr.expect_delete_from_namespace()
+            .withf(|ns, name| ns == "ns-c" && name == "shared")
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        mgr.expect_publish_event()
+            .withf(|seed, e| {
+                seed == "source/shared"
+                    && e.reason == Reason::SproutUpdated
+                    && e.namespace.as_deref() == Some("ns-b")
+            })
+            .times(1)
+            .return_const(true);
+        mgr.expect_publish_event()
+            .withf(|_, e| {
+                e.reason == Reason::SproutPruned && e.namespace.as_deref() == Some("ns-c")
+            })
+            .times(1)
+            .return_const(true);
+
+        grow_sprouts(cm.clone(), &mgr, &propagation(&cm), 4)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_grow_cluster_seed_into_every_namespace() {
+        let cluster_seed = ClusterSeed::new(
+            "shared",
+            ClusterSeedSpec {
+                kind: SproutKind::ConfigMap,
+                data: BTreeMap::from([("key".to_string(), "value".to_string())]),
+                binary_data: BTreeMap::new(),
+                targets: Default::default(),
+                drift_policy: DriftPolicy::Revert,
+            },
+        );
+        let cm = ConfigMap::from_cluster_seed(&cluster_seed);
+        assert_eq!(cm.hash(), seed(&[]).hash());
+
+        let mut mgr = MockResourceManager::<ConfigMap>::new();
+        mgr.expect_publish_event().return_const(true);
+        mgr.expect_list_namespaces().returning(|| {
+            Ok(vec![
+                namespace("source", &[]),
+                namespace("ns-a", &[]),
+                namespace("kube-system", &[]),
+            ])
+        });
+        mgr.expect_list_by_name().returning(|_| Ok(vec![]));
+        mgr.expect_get_in_namespace().returning(|_, _| Ok(None));
+        mgr.expect_list_requests().returning(|_| Ok(vec![]));
+        mgr.expect_create_in_namespace()
+            .withf(|ns, s: &ConfigMap| {
+                ["source", "ns-a"].contains(&ns) && is_sprout_of(s.meta(), "shared")
+            })
+            .times(2)
+            .returning(|_, _| Ok(()));
+
+        let propagation = Propagation::from_cluster_seed(&cluster_seed, &Config::default());
+        grow_sprouts(cm, &mgr, &propagation.unwrap(), 4)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_grow_sprouts_into_requested_namespaces() {
+        let cm = seed(&[(TARGETS_KEY, ""), (REQUESTABLE_KEY, "true")]);
+        let mut mgr = MockResourceManager::<ConfigMap>::new();
+        mgr.expect_publish_event().return_const(true);
+        mgr.expect_list_namespaces()
+            .returning(|| Ok(vec![namespace("ns-a", &[]), namespace("ns-b", &[])]));
+        mgr.expect_list_by_name().returning(|_| Ok(vec![]));
+        mgr.expect_get_in_namespace().returning(|_, _| Ok(None));
+        mgr.expect_list_requests().returning(|_| {
+            Ok(vec![
+                request("ns-b", SproutKind::ConfigMap),
+                request("ns-a", SproutKind::Secret),
+            ])
+        });
+        mgr.expect_create_in_namespace()
+            .withf(|ns, _| ns == "ns-b")
+            .times(1)
+            .returning(|_, _| Ok(()));
+        let hash = cm.hash();
+        mgr.expect_update_request_status()
+            .withf(move |req, status| {
+                req.namespace().as_deref() == Some("ns-b")
+                    && *status == SproutRequestStatus::ready(hash.clone())
+            })
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        grow_sprouts(cm.clone(), &mgr, &propagation(&cm), 4)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_is_desired_in_requested_namespace_no_longer_targeted() {
+        let cm = seed(&[(TARGETS_KEY, "ns-a"), (REQUESTABLE_KEY, "true")]);
+        let mut mgr = MockResourceManager::<ConfigMap>::new();
+        mgr.expect_list_requests()
+            .returning(|_| Ok(vec![request("ns-b", SproutKind::ConfigMap)]));
+        let propagation = propagation(&cm);
+        let desired = |ns: &str| {
+            let ns = namespace(ns, &[]);
+            let (mgr, propagation) = (&mgr, &propagation);
+            async move { is_desired_in("source/shared", &ns, propagation, mgr).await }
+        };
+
+        assert!(desired("ns-a").await.unwrap());
+        assert!(desired("ns-b").await.unwrap());
+        assert!(!desired("ns-c").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_grow_sprouts_rejects_requests_for_unrequestable_seed() {
+        let cm = seed(&[(TARGETS_KEY, "")]);
+        let mut mgr = MockResourceManager::<ConfigMap>::new();
+        mgr.expect_publish_event().return_const(true);
+        mgr.expect_list_namespaces()
+            .returning(|| Ok(vec![namespace("ns-a", &[])]));
+        mgr.expect_list_by_name().returning(|_| Ok(vec![]));
+        mgr.expect_get_in_namespace().returning(|_, _| Ok(None));
+        mgr.expect_list_requests()
+            .returning(|_| Ok(vec![request("ns-a", SproutKind::ConfigMap)]));
+        mgr.expect_create_in_namespace().never();
+        mgr.expect_update_request_status()
+            .withf(|_, status| {
+                status.error.as_deref() == Some("Seed 'source/shared' is not requestable")
+            })
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        grow_sprouts(cm.clone(), &mgr, &propagation(&cm), 4)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_delete_sprouts_only_deletes_sprouts_of_seed() {
+        let cm = seed(&[(NAMESPACE_SELECTOR_KEY, "team=payments")]);
+        let mut mgr = MockResourceManager::<ConfigMap>::new();
+        mgr.expect_publish_event().return_const(true);
+        let current = cm.clone();
+        mgr.expect_list_by_name().returning(move |_| {
+            let mut other = sprout_in("other", &current);
+            other.annotations_mut().insert(
+                "sprouter.geeko.me/sprout-of".to_string(),
+                "elsewhere/shared".to_string(),
+            );
+            Ok(vec![
+                current.clone(),
+                sprout_in("payments", &current),
+                sprout_in("billing", &current),
+                plain_in("plain"),
+                other,
+            ])
+        });
+        mgr.expect_delete_from_namespace()
+            .withf(|ns, name| (ns == "payments" || ns == "billing") && name == "shared")
+            .times(2)
+            .returning(|_, _| Ok(()));
+        mgr.expect_list_requests()
+            .returning(|_| Ok(vec![request("payments", SproutKind::ConfigMap)]));
+        mgr.expect_update_request_status()
+            .withf(|_, status| !status.ready)
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        delete_sprouts(cm, &mgr, 4).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_delete_sprouts_continues_past_failures() {
+        let cm = seed(&[]);
+        let mut mgr = MockResourceManager::<ConfigMap>::new();
+        mgr.expect_publish_event().return_const(true);
+        let current = cm.clone();
+        mgr.expect_list_by_name().returning(move |_| {
+            Ok(vec![
+                sprout_in("payments", &current),
+                sprout_in("billing", &current),
+            ])
+        });
+        mgr.expect_delete_from_namespace()
+            .withf(|ns, _| ns == "billing")
+            .returning(|_, _| Err(anyhow::anyhow!("forbidden")));
+        mgr.expect_delete_from_namespace()
+            .withf(|ns, _| ns == "payments")
+            .times(1)
+            .returning(|_, _| Ok(()));
+        mgr.expect_list_requests().returning(|_| Ok(vec![]));
+
+        let err = delete_sprouts(cm, &mgr, 4).await.unwrap_err();
+        assert!(err.to_string().starts_with("1 sprouts"));
+    }
+
+    #[tokio::test]
+    async fn test_prune_sprout_only_deletes_sprouts_of_seed() {
+        let mut mgr = MockResourceManager::<ConfigMap>::new();
+        mgr.expect_publish_event().return_const(true);
+        mgr.expect_get_in_namespace()
+            .returning(|_, _| Ok(Some(sprout_in("payments", &seed(&[])))));
+        mgr.expect_delete_from_namespace()
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        assert!(
+            !prune_sprout("other/shared", "shared", "payments", &mgr)
+                .await
+                .unwrap()
+        );
+        assert!(
+            prune_sprout("source/shared", "shared", "payments", &mgr)
+                .await
+                .unwrap()
+        );
+    }
+}
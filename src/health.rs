@@ -0,0 +1,144 @@
+// Copyright 2025, Jeroen van Erp <jeroen@geeko.me>
+// SPDX-License-Identifier: Apache-2.0
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use axum::Router;
+use axum::http::StatusCode;
+use axum::routing::get;
+use tracing::info;
+
+/// The health of the operator, served on `/healthz` and `/readyz`.
+pub static HEALTH: LazyLock<Health> = LazyLock::new(Health::default);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WatcherState {
+    /// Started, but no event received yet.
+    Starting(Instant),
+    Watching,
+    /// Failing since the given moment.
+    Failing(Instant),
+    Stopped,
+}
+
+#[derive(Default)]
+pub struct Health {
+    ready: AtomicBool,
+    watchers: Mutex<BTreeMap<&'static str, WatcherState>>,
+}
+
+impl Health {
+    /// Marks the operator ready, once the initial seed sync completed.
+    pub fn set_ready(&self) {
+        self.ready.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.ready.load(Ordering::Relaxed)
+    }
+
+    pub fn watcher_started(&self, watcher: &'static str) {
+        self.set_watcher(watcher, WatcherState::Starting(Instant::now()));
+    }
+
+    pub fn watcher_progressed(&self, watcher: &'static str) {
+        self.set_watcher(watcher, WatcherState::Watching);
+    }
+
+    pub fn watcher_failed(&self, watcher: &'static str) {
+        let mut watchers = self.watchers.lock().unwrap();
+        if !matches!(watchers.get(watcher), Some(WatcherState::Failing(_))) {
+            watchers.insert(watcher, WatcherState::Failing(Instant::now()));
+        }
+    }
+
+    pub fn watcher_stopped(&self, watcher: &'static str) {
+        self.set_watcher(watcher, WatcherState::Stopped);
+    }
+
+    fn set_watcher(&self, watcher: &'static str, state: WatcherState) {
+        self.watchers.lock().unwrap().insert(watcher, state);
+    }
+
+    /// The watchers that stopped, or that have not received their first event or kept failing
+    /// for longer than the threshold.
+    pub fn stalled(&self, threshold: Duration) -> Vec<&'static str> {
+        let now = Instant::now();
+        self.watchers
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, state)| match state {
+                WatcherState::Starting(since) | WatcherState::Failing(since) => {
+                    now.duration_since(*since) >= threshold
+                }
+                WatcherState::Watching => false,
+                WatcherState::Stopped => true,
+            })
+            .map(|(watcher, _)| *watcher)
+            .collect()
+    }
+}
+
+/// Serves `/healthz` and `/readyz` on the address until the process exits. Liveness fails when a
+/// watcher stalled for longer than the threshold.
+pub async fn serve(addr: SocketAddr, threshold: Duration) -> Result<()> {
+    let app = Router::new()
+        .route(
+            "/healthz",
+            get(move || async move {
+                let stalled = HEALTH.stalled(threshold);
+                if stalled.is_empty() {
+                    (StatusCode::OK, "ok".to_string())
+                } else {
+                    let msg = format!("stalled watchers: {}", stalled.join(", "));
+                    (StatusCode::SERVICE_UNAVAILABLE, msg)
+                }
+            }),
+        )
+        .route(
+            "/readyz",
+            get(|| async {
+                if HEALTH.is_ready() {
+                    (StatusCode::OK, "ok")
+                } else {
+                    (StatusCode::SERVICE_UNAVAILABLE, "initial sync in progress")
+                }
+            }),
+        );
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    info!("Serving health probes on {}", addr);
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stalled_watchers() {
+        let health = Health::default();
+        health.watcher_started("ConfigMap");
+        health.watcher_started("Secret");
+        health.watcher_started("Namespace");
+        assert!(health.stalled(Duration::from_secs(60)).is_empty());
+        assert_eq!(
+            health.stalled(Duration::ZERO),
+            vec!["ConfigMap", "Namespace", "Secret"]
+        );
+
+        health.watcher_progressed("ConfigMap");
+        health.watcher_failed("Secret");
+        health.watcher_progressed("Namespace");
+        assert_eq!(health.stalled(Duration::ZERO), vec!["Secret"]);
+
+        health.watcher_progressed("Secret");
+        health.watcher_stopped("Namespace");
+        assert_eq!(health.stalled(Duration::from_secs(60)), vec!["Namespace"]);
+    }
+}
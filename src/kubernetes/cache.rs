@@ -0,0 +1,279 @@
+// Copyright 2025, Jeroen van Erp <jeroen@geeko.me>
+// SPDX-License-Identifier: Apache-2.0
+use std::sync::Arc;
+
+use futures::{FutureExt, StreamExt};
+use k8s_openapi::api::core::v1::{ConfigMap, Namespace, Secret};
+use kube::{Api, Client, Resource};
+use kube_runtime::WatchStreamExt;
+use kube_runtime::reflector::{self, ObjectRef, Store, store::Writer};
+use kube_runtime::watcher::{Config as WatcherConfig, Event, watcher};
+use tokio::sync::broadcast;
+use tracing::{info, warn};
+
+use crate::health::HEALTH;
+use crate::metrics::METRICS;
+use crate::types::SproutRequest;
+use crate::utils::SPROUT_LABEL_KEY;
+
+/// The number of sprout events kept for a sprout controller that falls behind.
+const SPROUT_EVENTS: usize = 1024;
+
+/// Caches of the namespaces, the sprouts and the SproutRequests, kept up to date by reflectors,
+/// so reconciling an unchanged seed takes no API requests. A cache is only read once it has been
+/// listed in full; until then the API is used instead.
+#[derive(Clone)]
+pub struct Cache {
+    namespaces: Store<Namespace>,
+    config_maps: Store<ConfigMap>,
+    secrets: Store<Secret>,
+    requests: Store<SproutRequest>,
+    config_map_events: broadcast::Sender<Event<ConfigMap>>,
+    secret_events: broadcast::Sender<Event<Secret>>,
+}
+
+/// The writing side of the caches, consumed by `run`.
+pub struct Reflectors {
+    namespaces: Writer<Namespace>,
+    config_maps: Writer<ConfigMap>,
+    secrets: Writer<Secret>,
+    requests: Writer<SproutRequest>,
+    config_map_events: broadcast::Sender<Event<ConfigMap>>,
+    secret_events: broadcast::Sender<Event<Secret>>,
+}
+
+impl Cache {
+    pub fn new() -> (Self, Reflectors) {
+        let (namespaces, namespaces_writer) = reflector::store();
+        let (config_maps, config_maps_writer) = reflector::store();
+        let (secrets, secrets_writer) = reflector::store();
+        let (requests, requests_writer) = reflector::store();
+        let (config_map_events, _) = broadcast::channel(SPROUT_EVENTS);
+        let (secret_events, _) = broadcast::channel(SPROUT_EVENTS);
+        let cache = Self {
+            namespaces,
+            config_maps,
+            secrets,
+            requests,
+            config_map_events: config_map_events.clone(),
+            secret_events: secret_events.clone(),
+        };
+        let reflectors = Reflectors {
+            namespaces: namespaces_writer,
+            config_maps: config_maps_writer,
+            secrets: secrets_writer,
+            requests: requests_writer,
+            config_map_events,
+            secret_events,
+        };
+        (cache, reflectors)
+    }
+
+    /// Waits until every cache has been listed in full.
+    pub async fn wait_until_ready(&self) -> anyhow::Result<()> {
+        self.namespaces.wait_until_ready().await?;
+        self.config_maps.wait_until_ready().await?;
+        self.secrets.wait_until_ready().await?;
+        self.requests.wait_until_ready().await?;
+        Ok(())
+    }
+
+    pub fn namespaces(&self) -> Option<Vec<Arc<Namespace>>> {
+        ready(&self.namespaces).map(Store::state)
+    }
+
+    /// The cached sprout of kind `K` in the namespace, if any.
+    pub fn sprout<K: Cached>(&self, namespace: &str, name: &str) -> Option<Arc<K>> {
+        ready(K::store(self))?.get(&ObjectRef::new(name).within(namespace))
+    }
+
+    /// The sprouts of kind `K`, being the objects labelled `sprouter.geeko.me/sprout=true`.
+    pub fn sprouts<K: Cached>(&self) -> Option<Vec<Arc<K>>> {
+        ready(K::store(self)).map(Store::state)
+    }
+
+    pub fn requests(&self) -> Option<Vec<Arc<SproutRequest>>> {
+        ready(&self.requests).map(Store::state)
+    }
+
+    /// The changes to the sprouts of kind `K` from now on, as applied to the cache, so the
+    /// sprouts are not watched twice.
+    pub fn sprout_events<K: CachedSprout>(&self) -> broadcast::Receiver<Event<K>> {
+        K::events(self).subscribe()
+    }
+}
+
+/// The store, if it has been listed in full.
+fn ready<S>(store: &Store<S>) -> Option<&Store<S>>
+where
+    S: Resource + Clone + 'static,
+    <S as Resource>::DynamicType: Eq + std::hash::Hash + Clone,
+{
+    matches!(store.wait_until_ready().now_or_never(), Some(Ok(()))).then_some(store)
+}
+
+/// A kind of object kept in the caches.
+pub trait Cached: Resource<DynamicType = ()> + Clone + 'static {
+    fn store(cache: &Cache) -> &Store<Self>;
+}
+
+impl Cached for Namespace {
+    fn store(cache: &Cache) -> &Store<Self> {
+        &cache.namespaces
+    }
+}
+
+impl Cached for ConfigMap {
+    fn store(cache: &Cache) -> &Store<Self> {
+        &cache.config_maps
+    }
+}
+
+impl Cached for Secret {
+    fn store(cache: &Cache) -> &Store<Self> {
+        &cache.secrets
+    }
+}
+
+impl Cached for SproutRequest {
+    fn store(cache: &Cache) -> &Store<Self> {
+        &cache.requests
+    }
+}
+
+/// A kind of sprout kept in the caches, whose changes are passed on to its controller.
+pub trait CachedSprout: Cached {
+    fn events(cache: &Cache) -> &broadcast::Sender<Event<Self>>;
+}
+
+impl CachedSprout for ConfigMap {
+    fn events(cache: &Cache) -> &broadcast::Sender<Event<Self>> {
+        &cache.config_map_events
+    }
+}
+
+impl CachedSprout for Secret {
+    fn events(cache: &Cache) -> &broadcast::Sender<Event<Self>> {
+        &cache.secret_events
+    }
+}
+
+/// Keeps the caches up to date for as long as the process runs.
+pub async fn run(client: Client, reflectors: Reflectors) -> anyhow::Result<()> {
+    let sprouts = WatcherConfig::default().labels(&format!("{}=true", SPROUT_LABEL_KEY));
+    info!("Starting cache reflectors...");
+    tokio::join!(
+        reflect(
+            Api::all(client.clone()),
+            WatcherConfig::default(),
+            reflectors.namespaces,
+            None,
+            "NamespaceCache",
+        ),
+        reflect(
+            Api::all(client.clone()),
+            sprouts.clone(),
+            reflectors.config_maps,
+            Some(reflectors.config_map_events),
+            "ConfigMapCache",
+        ),
+        reflect(
+            Api::all(client.clone()),
+            sprouts,
+            reflectors.secrets,
+            Some(reflectors.secret_events),
+            "SecretCache",
+        ),
+        reflect(
+            Api::all(client.clone()),
+            WatcherConfig::default(),
+            reflectors.requests,
+            None,
+            "SproutRequestCache",
+        ),
+    );
+    Ok(())
+}
+
+/// Keeps the cache up to date through the writer, passing the applied changes on to the
+/// subscribers of `events`, if any.
+async fn reflect<K>(
+    api: Api<K>,
+    config: WatcherConfig,
+    writer: Writer<K>,
+    events: Option<broadcast::Sender<Event<K>>>,
+    name: &'static str,
+) where
+    K: Resource + Clone + serde::de::DeserializeOwned + std::fmt::Debug + Send + Sync + 'static,
+    <K as Resource>::DynamicType: Eq + std::hash::Hash + Clone + Default,
+{
+    let mut stream = watcher(api, config)
+        .default_backoff()
+        .reflect(writer)
+        .boxed();
+    HEALTH.watcher_started(name);
+    while let Some(event) = stream.next().await {
+        match event {
+            Ok(event) => {
+                HEALTH.watcher_progressed(name);
+                if let Some(events) = &events {
+                    // There are no subscribers until the controllers run, e.g. on a standby
+                    let _ = events.send(event);
+                }
+            }
+            Err(e) => {
+                warn!("{} reflector failed, restarting with backoff: {}", name, e);
+                METRICS.record_watcher_restart(name);
+                HEALTH.watcher_failed(name);
+            }
+        }
+    }
+    HEALTH.watcher_stopped(name);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kube_runtime::watcher::Event;
+
+    fn config_map(ns: &str, name: &str) -> ConfigMap {
+        let mut cm = ConfigMap::default();
+        cm.metadata.namespace = Some(ns.to_string());
+        cm.metadata.name = Some(name.to_string());
+        cm
+    }
+
+    #[test]
+    fn test_sprouts_are_read_once_listed() {
+        let (cache, mut reflectors) = Cache::new();
+        reflectors.config_maps.apply_watcher_event(&Event::Init);
+        reflectors
+            .config_maps
+            .apply_watcher_event(&Event::InitApply(config_map("ns-a", "shared")));
+        assert!(cache.sprouts::<ConfigMap>().is_none());
+
+        reflectors.config_maps.apply_watcher_event(&Event::InitDone);
+        assert_eq!(cache.sprouts::<ConfigMap>().map(|s| s.len()), Some(1));
+        assert!(cache.sprout::<ConfigMap>("ns-a", "shared").is_some());
+        assert!(cache.sprout::<ConfigMap>("ns-b", "shared").is_none());
+        assert!(cache.sprouts::<Secret>().is_none());
+        assert!(cache.sprout::<Secret>("ns-a", "shared").is_none());
+        assert!(cache.namespaces().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_sprout_events_are_passed_on_to_subscribers() {
+        let (cache, reflectors) = Cache::new();
+        let mut events = cache.sprout_events::<ConfigMap>();
+        reflectors
+            .config_map_events
+            .send(Event::Delete(config_map("ns-a", "shared")))
+            .unwrap();
+
+        match events.recv().await.unwrap() {
+            Event::Delete(cm) => assert_eq!(cm.metadata.namespace.as_deref(), Some("ns-a")),
+            _ => panic!("expected the deletion of the sprout"),
+        }
+        assert!(cache.sprout_events::<Secret>().is_empty());
+    }
+}
@@ -0,0 +1,355 @@
+// Copyright 2025, Jeroen van Erp <jeroen@geeko.me>
+// SPDX-License-Identifier: Apache-2.0
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use k8s_openapi::api::core::v1::ObjectReference;
+use kube::Client;
+use kube::Resource;
+use kube_runtime::events::{Event, EventType, Recorder, Reporter};
+use tracing::warn;
+
+use crate::kubernetes::throttle::Throttle;
+use crate::types::ClusterSeed;
+
+const CONTROLLER: &str = "sprouter";
+
+/// A seed, by kind and `namespace/name`, and the namespace of one of its sprouts.
+type WarningKey = (String, Option<String>);
+
+/// The stable reason codes of the Events Sprouter records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reason {
+    SproutCreated,
+    SproutUpdated,
+    SproutPruned,
+    SproutDeleted,
+    SproutReverted,
+    SproutDrifted,
+    SproutConflict,
+    SproutCreateFailed,
+    SproutUpdateFailed,
+    InvalidPropagation,
+}
+
+impl Reason {
+    /// Whether the reason is a state that lasts until something changes, rather than something
+    /// that happened.
+    fn is_lasting(self) -> bool {
+        matches!(
+            self,
+            Reason::SproutConflict | Reason::SproutDrifted | Reason::InvalidPropagation
+        )
+    }
+
+    pub fn is_warning(self) -> bool {
+        matches!(
+            self,
+            Reason::SproutConflict
+                | Reason::SproutDrifted
+                | Reason::SproutCreateFailed
+                | Reason::SproutUpdateFailed
+                | Reason::InvalidPropagation
+        )
+    }
+
+    fn action(self) -> &'static str {
+        match self {
+            Reason::SproutCreated | Reason::SproutCreateFailed => "Create",
+            Reason::SproutUpdated | Reason::SproutReverted | Reason::SproutUpdateFailed => "Update",
+            Reason::SproutPruned | Reason::SproutDeleted => "Delete",
+            Reason::SproutConflict | Reason::SproutDrifted | Reason::InvalidPropagation => "Sprout",
+        }
+    }
+
+    /// Whether the sprout exists after the action, so the Event is also recorded on it.
+    fn concerns_sprout(self) -> bool {
+        matches!(
+            self,
+            Reason::SproutCreated
+                | Reason::SproutUpdated
+                | Reason::SproutReverted
+                | Reason::SproutDrifted
+                | Reason::SproutConflict
+                | Reason::SproutUpdateFailed
+        )
+    }
+}
+
+impl fmt::Display for Reason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+/// Something that happened to a seed, or to its sprout in `namespace`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SproutEvent {
+    pub reason: Reason,
+    pub namespace: Option<String>,
+    pub note: String,
+}
+
+impl SproutEvent {
+    pub fn new(reason: Reason, namespace: Option<&str>, note: impl Into<String>) -> Self {
+        Self {
+            reason,
+            namespace: namespace.map(str::to_string),
+            note: note.into(),
+        }
+    }
+}
+
+/// Records the Events of all seeds through a single Recorder, so repeated Events are counted in
+/// a series. Warnings about a lasting state, such as a conflicting object, are recorded once until
+/// the state ends, rather than on every sync.
+#[derive(Clone)]
+pub struct Events {
+    recorder: Recorder,
+    throttle: Arc<Throttle>,
+    /// The lasting warning recorded for each seed, by kind and seed, and namespace of the sprout.
+    warned: Arc<Mutex<HashMap<WarningKey, Reason>>>,
+}
+
+impl Events {
+    pub fn new(client: Client, throttle: Arc<Throttle>) -> Self {
+        Self {
+            recorder: Recorder::new(client, reporter()),
+            throttle,
+            warned: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Records the Event on the seed, and on the sprout if it exists. Failing to record an Event
+    /// is logged, as it does not affect the sprouts. Returns whether the Event is new, which a
+    /// repeated lasting warning is not.
+    pub async fn publish<K>(&self, seed: &str, event: &SproutEvent) -> bool
+    where
+        K: Resource,
+        <K as Resource>::DynamicType: Default,
+    {
+        let key = warning_key::<K>(seed, event.namespace.as_deref());
+        if !self.is_new(key, event) {
+            return false;
+        }
+        let name = seed.rsplit('/').next().unwrap_or_default();
+        let seed_ref = seed_reference::<K>(seed);
+        let sprout_ref = event
+            .namespace
+            .as_deref()
+            .map(|ns| sprout_reference::<K>(ns, name));
+        self.record(seed, seed_ref, sprout_ref, event).await;
+        true
+    }
+
+    /// Records the Event on a cluster scoped object itself, such as a SproutPolicy or a
+    /// ClusterSeed, whatever the kind of its sprouts. Returns whether the Event is new, like
+    /// `publish`.
+    pub async fn publish_on<R>(&self, object: &R, event: &SproutEvent) -> bool
+    where
+        R: Resource<DynamicType = ()>,
+    {
+        let name = object.meta().name.clone().unwrap_or_default();
+        if !self.is_new(warning_key::<R>(&name, None), event) {
+            return false;
+        }
+        self.record(&name, object.object_ref(&()), None, event)
+            .await;
+        true
+    }
+
+    /// Whether the Event is to be recorded, which a repeated lasting warning is not.
+    fn is_new(&self, key: WarningKey, event: &SproutEvent) -> bool {
+        let mut warned = self.warned.lock().unwrap();
+        if event.reason.is_lasting() {
+            return warned.insert(key, event.reason) != Some(event.reason);
+        } else if event.namespace.is_some() {
+            warned.remove(&key);
+        }
+        true
+    }
+
+    async fn record(
+        &self,
+        seed: &str,
+        seed_ref: ObjectReference,
+        sprout_ref: Option<ObjectReference>,
+        event: &SproutEvent,
+    ) {
+        let ev = Event {
+            type_: if event.reason.is_warning() {
+                EventType::Warning
+            } else {
+                EventType::Normal
+            },
+            reason: event.reason.to_string(),
+            note: Some(event.note.clone()),
+            action: event.reason.action().to_string(),
+            secondary: sprout_ref.clone(),
+        };
+        self.throttle.acquire().await;
+        if let Err(e) = self.recorder.publish(&ev, &seed_ref).await {
+            warn!("Recording {} on '{}' failed: {}", event.reason, seed, e);
+        }
+        if let Some(sprout_ref) = sprout_ref.filter(|_| event.reason.concerns_sprout()) {
+            let ev = Event {
+                secondary: Some(seed_ref),
+                ..ev
+            };
+            self.throttle.acquire().await;
+            if let Err(e) = self.recorder.publish(&ev, &sprout_ref).await {
+                warn!(
+                    "Recording {} on sprout of '{}' failed: {}",
+                    event.reason, seed, e
+                );
+            }
+        }
+    }
+
+    /// Marks the lasting warning about the seed, or about its sprout in `namespace`, as ended, so
+    /// it is recorded again if it recurs.
+    pub fn recovered<K>(&self, seed: &str, namespace: Option<&str>)
+    where
+        K: Resource,
+        <K as Resource>::DynamicType: Default,
+    {
+        self.warned
+            .lock()
+            .unwrap()
+            .remove(&warning_key::<K>(seed, namespace));
+    }
+}
+
+fn warning_key<K>(seed: &str, namespace: Option<&str>) -> WarningKey
+where
+    K: Resource,
+    <K as Resource>::DynamicType: Default,
+{
+    let dt = Default::default();
+    (
+        format!("{} {}", K::kind(&dt), seed),
+        namespace.map(str::to_string),
+    )
+}
+
+fn reporter() -> Reporter {
+    Reporter {
+        controller: CONTROLLER.to_string(),
+        instance: std::env::var("POD_NAME").ok(),
+    }
+}
+
+/// The reference to a seed given as `namespace/name`, or to the ClusterSeed given by its name.
+pub fn seed_reference<K>(seed: &str) -> ObjectReference
+where
+    K: Resource,
+    <K as Resource>::DynamicType: Default,
+{
+    match seed.split_once('/') {
+        Some((namespace, name)) => sprout_reference::<K>(namespace, name),
+        None => ObjectReference {
+            api_version: Some(ClusterSeed::api_version(&()).to_string()),
+            kind: Some(ClusterSeed::kind(&()).to_string()),
+            name: Some(seed.to_string()),
+            ..Default::default()
+        },
+    }
+}
+
+fn sprout_reference<K>(namespace: &str, name: &str) -> ObjectReference
+where
+    K: Resource,
+    <K as Resource>::DynamicType: Default,
+{
+    let dt = Default::default();
+    ObjectReference {
+        api_version: Some(K::api_version(&dt).to_string()),
+        kind: Some(K::kind(&dt).to_string()),
+        namespace: Some(namespace.to_string()),
+        name: Some(name.to_string()),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sprout::kind::SproutKind;
+    use crate::types::ClusterSeedSpec;
+    use k8s_openapi::api::core::v1::ConfigMap;
+    use kube::client::Body;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Events recording through a client that counts its requests and rejects them all.
+    fn events() -> (Events, Arc<AtomicUsize>) {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+        let service = tower::service_fn(move |_: http::Request<Body>| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            async {
+                let response = http::Response::builder()
+                    .status(500)
+                    .body(Body::from(Vec::new()))
+                    .unwrap();
+                Ok::<_, std::convert::Infallible>(response)
+            }
+        });
+        let throttle = Arc::new(Throttle::new(0.0, 1));
+        (
+            Events::new(Client::new(service, "default"), throttle),
+            requests,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_lasting_warnings_are_recorded_once() {
+        let (events, requests) = events();
+        let conflict = SproutEvent::new(Reason::SproutConflict, Some("ns-a"), "conflict");
+        assert!(
+            events
+                .publish::<ConfigMap>("source/shared", &conflict)
+                .await
+        );
+        let recorded = requests.load(Ordering::SeqCst);
+        assert!(recorded > 0);
+
+        assert!(
+            !events
+                .publish::<ConfigMap>("source/shared", &conflict)
+                .await
+        );
+        assert_eq!(requests.load(Ordering::SeqCst), recorded);
+
+        events.recovered::<ConfigMap>("source/shared", Some("ns-a"));
+        assert!(
+            events
+                .publish::<ConfigMap>("source/shared", &conflict)
+                .await
+        );
+        assert_eq!(requests.load(Ordering::SeqCst), 2 * recorded);
+    }
+
+    #[tokio::test]
+    async fn test_cluster_seed_warnings_are_keyed_by_cluster_seed() {
+        let (events, requests) = events();
+        let seed = ClusterSeed::new(
+            "shared",
+            ClusterSeedSpec {
+                kind: SproutKind::Secret,
+                data: Default::default(),
+                binary_data: Default::default(),
+                targets: Default::default(),
+                drift_policy: Default::default(),
+            },
+        );
+        let invalid = SproutEvent::new(Reason::InvalidPropagation, None, "invalid");
+        assert!(events.publish_on(&seed, &invalid).await);
+        assert!(!events.publish_on(&seed, &invalid).await);
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+        events.recovered::<ClusterSeed>("shared", None);
+        assert!(events.publish_on(&seed, &invalid).await);
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+    }
+}
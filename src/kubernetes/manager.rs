@@ -1,12 +1,20 @@
 // Copyright 2025, Jeroen van Erp <jeroen@geeko.me>
 // SPDX-License-Identifier: Apache-2.0
+use std::sync::Arc;
+
 use anyhow::Result;
 use k8s_openapi::api::core::v1::Namespace;
 use kube::core::NamespaceResourceScope;
 use kube::{
     Api, Client,
-    api::{ListParams, PostParams, ResourceExt},
+    api::{ListParams, Patch, PatchParams, PostParams, ResourceExt},
 };
+use serde_json::json;
+
+use crate::kubernetes::cache::{Cache, Cached};
+use crate::kubernetes::events::{Events, SproutEvent};
+use crate::kubernetes::throttle::Throttle;
+use crate::types::{SproutRequest, SproutRequestStatus};
 
 #[cfg_attr(test, mockall::automock)]
 #[async_trait::async_trait]
@@ -21,11 +29,24 @@ where
         + Sync,
     <K as kube::Resource>::DynamicType: Default,
 {
-    async fn list_namespaces(&self) -> Result<Vec<String>>;
+    async fn list_namespaces(&self) -> Result<Vec<Namespace>>;
     async fn create_in_namespace(&self, ns: &str, resource: &K) -> Result<()>;
     async fn update_in_namespace(&self, ns: &str, resource: &K) -> Result<()>;
     async fn delete_from_namespace(&self, ns: &str, name: &str) -> Result<()>;
+    /// The object named `name` in the namespace, whether or not it is a sprout.
     async fn get_in_namespace(&self, ns: &str, name: &str) -> Result<Option<K>>;
+    /// The sprouts named `name`, in every namespace. Objects with that name that are no sprout
+    /// may be left out.
+    async fn list_by_name(&self, name: &str) -> Result<Vec<K>>;
+    async fn list_requests(&self, seed: &str) -> Result<Vec<SproutRequest>>;
+    async fn update_request_status(
+        &self,
+        request: &SproutRequest,
+        status: &SproutRequestStatus,
+    ) -> Result<()>;
+    /// Records an Event on the seed, given as `namespace/name`, and on its sprout. Returns whether
+    /// the Event is new, which a repeated lasting warning is not.
+    async fn publish_event(&self, seed: &str, event: &SproutEvent) -> bool;
 }
 
 pub struct KubeResourceManager<K>
@@ -40,6 +61,9 @@ where
 {
     _marker: std::marker::PhantomData<K>,
     client: Client,
+    throttle: Arc<Throttle>,
+    cache: Cache,
+    events: Events,
 }
 
 impl<K> KubeResourceManager<K>
@@ -52,10 +76,13 @@ where
         + 'static,
     <K as kube::Resource>::DynamicType: Default,
 {
-    pub fn new(client: Client) -> Self {
+    pub fn new(client: Client, throttle: Arc<Throttle>, cache: Cache, events: Events) -> Self {
         Self {
             _marker: std::marker::PhantomData,
             client,
+            throttle,
+            cache,
+            events,
         }
     }
 }
@@ -70,34 +97,37 @@ where
         + std::fmt::Debug
         + Send
         + Sync
-        + 'static,
+        + Cached,
     <K as kube::Resource>::DynamicType: Default,
 {
-    async fn list_namespaces(&self) -> Result<Vec<String>> {
+    async fn list_namespaces(&self) -> Result<Vec<Namespace>> {
+        if let Some(namespaces) = self.cache.namespaces() {
+            return Ok(namespaces.iter().map(|ns| (**ns).clone()).collect());
+        }
+        self.throttle.acquire().await;
         let ns_api: Api<Namespace> = Api::all(self.client.clone());
         let namespaces = ns_api.list(&ListParams::default()).await?;
-        Ok(namespaces.iter().map(|n| n.name_any()).collect())
+        Ok(namespaces.items)
     }
 
     async fn create_in_namespace(&self, ns: &str, resource: &K) -> Result<()> {
+        self.throttle.acquire().await;
         let api: Api<K> = Api::namespaced(self.client.clone(), ns);
         let mut res = resource.clone();
         res.meta_mut().namespace = Some(ns.to_string());
         res.meta_mut().resource_version = None;
         res.meta_mut().uid = None;
 
-        let _ = api.create(&PostParams::default(), &res).await.or_else(|e| {
-            if let kube::Error::Api(err) = &e {
-                if err.code == 409 {
-                    return Ok(res);
-                }
-            }
-            Err(e)
-        });
-        Ok(())
+        match api.create(&PostParams::default(), &res).await {
+            Ok(_) => Ok(()),
+            // Created by someone else in the meantime
+            Err(kube::Error::Api(err)) if err.code == 409 => Ok(()),
+            Err(e) => Err(e.into()),
+        }
     }
 
     async fn update_in_namespace(&self, ns: &str, resource: &K) -> Result<()> {
+        self.throttle.acquire().await;
         let api: Api<K> = Api::namespaced(self.client.clone(), ns);
         let mut res = resource.clone();
         res.meta_mut().namespace = Some(ns.to_string());
@@ -110,14 +140,202 @@ where
     }
 
     async fn delete_from_namespace(&self, ns: &str, name: &str) -> Result<()> {
+        self.throttle.acquire().await;
         let api: Api<K> = Api::namespaced(self.client.clone(), ns);
-        let _ = api.delete(name, &Default::default()).await;
-        Ok(())
+        match api.delete(name, &Default::default()).await {
+            Ok(_) => Ok(()),
+            // Deleted by someone else in the meantime
+            Err(kube::Error::Api(err)) if err.code == 404 => Ok(()),
+            Err(e) => Err(e.into()),
+        }
     }
 
     async fn get_in_namespace(&self, ns: &str, name: &str) -> Result<Option<K>> {
+        // Only sprouts are cached, so anything else is looked up
+        if let Some(sprout) = self.cache.sprout::<K>(ns, name) {
+            return Ok(Some((*sprout).clone()));
+        }
+        self.throttle.acquire().await;
         let api: Api<K> = Api::namespaced(self.client.clone(), ns);
         let res = api.get_opt(name).await?;
         Ok(res)
     }
+
+    async fn list_by_name(&self, name: &str) -> Result<Vec<K>> {
+        if let Some(sprouts) = self.cache.sprouts::<K>() {
+            return Ok(sprouts
+                .iter()
+                .filter(|s| s.name_any() == name)
+                .map(|s| (**s).clone())
+                .collect());
+        }
+        self.throttle.acquire().await;
+        let api: Api<K> = Api::all(self.client.clone());
+        let lp = ListParams::default().fields(&format!("metadata.name={}", name));
+        Ok(api.list(&lp).await?.items)
+    }
+
+    async fn list_requests(&self, seed: &str) -> Result<Vec<SproutRequest>> {
+        if let Some(requests) = self.cache.requests() {
+            return Ok(requests
+                .iter()
+                .filter(|r| r.spec.seed == seed)
+                .map(|r| (**r).clone())
+                .collect());
+        }
+        self.throttle.acquire().await;
+        let api: Api<SproutRequest> = Api::all(self.client.clone());
+        match api.list(&ListParams::default()).await {
+            Ok(requests) => Ok(requests
+                .items
+                .into_iter()
+                .filter(|r| r.spec.seed == seed)
+                .collect()),
+            // The SproutRequest CRD is not installed, so nothing can have been requested
+            Err(kube::Error::Api(err)) if err.code == 404 => Ok(vec![]),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn update_request_status(
+        &self,
+        request: &SproutRequest,
+        status: &SproutRequestStatus,
+    ) -> Result<()> {
+        self.throttle.acquire().await;
+        let api: Api<SproutRequest> = Api::namespaced(
+            self.client.clone(),
+            &request.namespace().unwrap_or_default(),
+        );
+        // Explicit nulls, so a merge patch clears fields that are no longer set
+        let patch = json!({
+            "status": {
+                "ready": status.ready,
+                "seedHash": status.seed_hash,
+                "error": status.error,
+            }
+        });
+        api.patch_status(
+            &request.name_any(),
+            &PatchParams::default(),
+            &Patch::Merge(&patch),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn publish_event(&self, seed: &str, event: &SproutEvent) -> bool {
+        self.events.publish::<K>(seed, event).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::grower::{delete_sprouts, grow_sprout};
+    use crate::sprout::kind::SproutKind;
+    use crate::sprout::policy::Propagation;
+    use crate::utils::{ANNOTATION_KEY, create_sprout};
+    use http::{Method, Request, Response};
+    use k8s_openapi::api::core::v1::ConfigMap;
+    use kube::Resource;
+    use kube::client::Body;
+    use serde_json::Value;
+    use std::collections::BTreeMap;
+
+    /// A client of an API server that answers every request as `route` tells it to.
+    fn client(route: fn(&Method, &str) -> (u16, Value)) -> Client {
+        let service = tower::service_fn(move |req: Request<Body>| async move {
+            let (code, body) = route(req.method(), req.uri().path());
+            let response = Response::builder()
+                .status(code)
+                .body(Body::from(serde_json::to_vec(&body).unwrap()))
+                .unwrap();
+            Ok::<_, std::convert::Infallible>(response)
+        });
+        Client::new(service, "default")
+    }
+
+    fn status(code: u16) -> (u16, Value) {
+        let status = json!({
+            "kind": "Status",
+            "apiVersion": "v1",
+            "status": "Failure",
+            "message": "rejected",
+            "code": code,
+        });
+        (code, status)
+    }
+
+    fn manager(route: fn(&Method, &str) -> (u16, Value)) -> KubeResourceManager<ConfigMap> {
+        let (cache, _) = Cache::new();
+        let client = client(route);
+        let throttle = Arc::new(Throttle::new(0.0, 1));
+        let events = Events::new(client.clone(), throttle.clone());
+        KubeResourceManager::new(client, throttle, cache, events)
+    }
+
+    fn seed() -> ConfigMap {
+        let mut cm = ConfigMap::default();
+        cm.metadata.name = Some("shared".to_string());
+        cm.metadata.namespace = Some("source".to_string());
+        cm.metadata.annotations = Some(BTreeMap::from([(
+            ANNOTATION_KEY.to_string(),
+            "true".to_string(),
+        )]));
+        cm
+    }
+
+    #[tokio::test]
+    async fn test_create_fails_unless_conflicting() {
+        let sprout = create_sprout(seed(), &None);
+        let mgr = manager(|_, _| status(409));
+        assert!(mgr.create_in_namespace("ns-a", &sprout).await.is_ok());
+        let mgr = manager(|_, _| status(403));
+        assert!(mgr.create_in_namespace("ns-a", &sprout).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_delete_fails_unless_gone() {
+        let mgr = manager(|_, _| status(404));
+        assert!(mgr.delete_from_namespace("ns-a", "shared").await.is_ok());
+        let mgr = manager(|_, _| status(403));
+        assert!(mgr.delete_from_namespace("ns-a", "shared").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_grow_sprout_fails_on_rejected_create() {
+        let mgr = manager(|method, _| match *method {
+            Method::POST => status(403),
+            _ => status(404),
+        });
+        let cm = seed();
+        let propagation =
+            Propagation::from_annotations(cm.meta(), SproutKind::ConfigMap, &Config::default())
+                .unwrap();
+        let (sprout, h) = propagation.sprout(cm);
+        let result = grow_sprout(&sprout, &h, "ns-a", None, &propagation, &mgr).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_delete_sprouts_fails_on_rejected_delete() {
+        let mgr = manager(|method, path| match (method, path) {
+            (&Method::GET, "/api/v1/configmaps") => {
+                let mut sprout = create_sprout(seed(), &None);
+                sprout.metadata.namespace = Some("ns-a".to_string());
+                let list = json!({
+                    "kind": "ConfigMapList",
+                    "apiVersion": "v1",
+                    "metadata": {},
+                    "items": [sprout],
+                });
+                (200, list)
+            }
+            (&Method::DELETE, _) => status(403),
+            _ => status(404),
+        });
+        assert!(delete_sprouts(seed(), &mgr, 4).await.is_err());
+    }
 }
@@ -1,3 +1,6 @@
 // Copyright 2025, Jeroen van Erp <jeroen@geeko.me>
 // SPDX-License-Identifier: Apache-2.0
+pub mod cache;
+pub mod events;
 pub mod manager;
+pub mod throttle;
@@ -0,0 +1,75 @@
+// Copyright 2025, Jeroen van Erp <jeroen@geeko.me>
+// SPDX-License-Identifier: Apache-2.0
+use std::time::Duration;
+
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+
+/// A client-side token bucket limiting the API requests made while growing sprouts: up to
+/// `burst` requests at once, refilled at `qps` requests per second.
+pub struct Throttle {
+    qps: f64,
+    burst: f64,
+    /// The tokens available at the given moment.
+    bucket: Mutex<(f64, Instant)>,
+}
+
+impl Throttle {
+    /// A throttle with a full bucket. A `qps` of zero or less disables throttling.
+    pub fn new(qps: f64, burst: u32) -> Self {
+        let burst = f64::from(burst.max(1));
+        Self {
+            qps,
+            burst,
+            bucket: Mutex::new((burst, Instant::now())),
+        }
+    }
+
+    /// Waits until a request may be made. Waiters are served in order, as the wait for a token
+    /// happens while holding the bucket.
+    pub async fn acquire(&self) {
+        if self.qps <= 0.0 {
+            return;
+        }
+        let mut bucket = self.bucket.lock().await;
+        let (tokens, since) = *bucket;
+        let now = Instant::now();
+        let tokens = (tokens + now.duration_since(since).as_secs_f64() * self.qps).min(self.burst);
+        if tokens >= 1.0 {
+            *bucket = (tokens - 1.0, now);
+            return;
+        }
+        let wait = Duration::from_secs_f64((1.0 - tokens) / self.qps);
+        tokio::time::sleep(wait).await;
+        *bucket = (0.0, now + wait);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_acquire_allows_burst_then_qps() {
+        let throttle = Throttle::new(2.0, 3);
+        let start = Instant::now();
+        for _ in 0..3 {
+            throttle.acquire().await;
+        }
+        assert_eq!(start.elapsed(), Duration::ZERO);
+
+        throttle.acquire().await;
+        throttle.acquire().await;
+        assert_eq!(start.elapsed(), Duration::from_secs(1));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_acquire_unthrottled() {
+        let throttle = Throttle::new(0.0, 1);
+        let start = Instant::now();
+        for _ in 0..10 {
+            throttle.acquire().await;
+        }
+        assert_eq!(start.elapsed(), Duration::ZERO);
+    }
+}
@@ -0,0 +1,186 @@
+// Copyright 2025, Jeroen van Erp <jeroen@geeko.me>
+// SPDX-License-Identifier: Apache-2.0
+use std::time::{Duration, Instant};
+
+use anyhow::{Result, bail};
+use k8s_openapi::api::coordination::v1::{Lease, LeaseSpec};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::MicroTime;
+use k8s_openapi::chrono::{DateTime, Utc};
+use kube::api::{ObjectMeta, PostParams};
+use kube::{Api, Client};
+use tracing::{info, warn};
+
+/// How long a lease is valid without being renewed.
+const LEASE_DURATION: Duration = Duration::from_secs(15);
+/// How often the leader renews its lease, and standby replicas try to acquire it.
+const RETRY_PERIOD: Duration = Duration::from_secs(3);
+
+/// Elects a single active replica through a coordination.k8s.io Lease.
+pub struct LeaderElector {
+    api: Api<Lease>,
+    name: String,
+    identity: String,
+}
+
+impl LeaderElector {
+    /// An elector for the lease with the given name in the namespace of the client.
+    pub fn new(client: Client, name: &str, identity: &str) -> Self {
+        Self {
+            api: Api::default_namespaced(client),
+            name: name.to_string(),
+            identity: identity.to_string(),
+        }
+    }
+
+    /// Waits until this replica holds the lease.
+    pub async fn acquire(&self) -> Result<()> {
+        info!(
+            "Waiting to acquire lease '{}' as '{}'",
+            self.name, self.identity
+        );
+        loop {
+            match self.try_acquire_or_renew().await {
+                Ok(true) => break,
+                Ok(false) => {}
+                Err(e) => warn!("Acquiring lease '{}' failed: {}", self.name, e),
+            }
+            tokio::time::sleep(RETRY_PERIOD).await;
+        }
+        info!("Acquired lease '{}' as '{}'", self.name, self.identity);
+        Ok(())
+    }
+
+    /// Renews the lease for as long as this replica holds it. Returns an error once the lease is
+    /// lost, either to another replica or because it could not be renewed in time.
+    pub async fn keep(&self) -> Result<()> {
+        let mut renewed = Instant::now();
+        loop {
+            tokio::time::sleep(RETRY_PERIOD).await;
+            match self.try_acquire_or_renew().await {
+                Ok(true) => renewed = Instant::now(),
+                Ok(false) => bail!("Lost lease '{}' to another replica", self.name),
+                Err(e) => warn!("Renewing lease '{}' failed: {}", self.name, e),
+            }
+            if renewed.elapsed() > LEASE_DURATION {
+                bail!("Lease '{}' could not be renewed in time", self.name);
+            }
+        }
+    }
+
+    /// Gives up the lease, so a standby replica can take over without waiting for it to expire.
+    pub async fn release(&self) -> Result<()> {
+        let Some(mut lease) = self.api.get_opt(&self.name).await? else {
+            return Ok(());
+        };
+        let spec = lease.spec.get_or_insert_with(Default::default);
+        if spec.holder_identity.as_deref() != Some(&self.identity) {
+            return Ok(());
+        }
+        spec.holder_identity = None;
+        spec.lease_duration_seconds = Some(1);
+        self.api
+            .replace(&self.name, &PostParams::default(), &lease)
+            .await?;
+        info!("Released lease '{}'", self.name);
+        Ok(())
+    }
+
+    async fn try_acquire_or_renew(&self) -> Result<bool> {
+        let now = Utc::now();
+        let result = match self.api.get_opt(&self.name).await? {
+            None => {
+                let lease = Lease {
+                    metadata: ObjectMeta {
+                        name: Some(self.name.clone()),
+                        ..Default::default()
+                    },
+                    spec: Some(self.spec(None, now)),
+                };
+                self.api.create(&PostParams::default(), &lease).await
+            }
+            Some(lease) if can_acquire(lease.spec.as_ref(), &self.identity, now) => {
+                let lease = Lease {
+                    spec: Some(self.spec(lease.spec.as_ref(), now)),
+                    ..lease
+                };
+                self.api
+                    .replace(&self.name, &PostParams::default(), &lease)
+                    .await
+            }
+            Some(_) => return Ok(false),
+        };
+        match result {
+            Ok(_) => Ok(true),
+            // Another replica acquired or renewed the lease in the meantime
+            Err(kube::Error::Api(err)) if err.code == 409 => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// The spec of the lease held by this replica, renewed at `now`.
+    fn spec(&self, current: Option<&LeaseSpec>, now: DateTime<Utc>) -> LeaseSpec {
+        let held = current.and_then(|s| s.holder_identity.as_deref()) == Some(&self.identity);
+        let transitions = current
+            .and_then(|s| s.lease_transitions)
+            .unwrap_or_default();
+        LeaseSpec {
+            holder_identity: Some(self.identity.clone()),
+            lease_duration_seconds: Some(LEASE_DURATION.as_secs() as i32),
+            acquire_time: if held {
+                current.and_then(|s| s.acquire_time.clone())
+            } else {
+                Some(MicroTime(now))
+            },
+            renew_time: Some(MicroTime(now)),
+            lease_transitions: Some(if held || current.is_none() {
+                transitions
+            } else {
+                transitions + 1
+            }),
+        }
+    }
+}
+
+/// Whether `identity` may take the lease at `now`: because it holds the lease already, nobody
+/// does, or the holder did not renew it in time.
+fn can_acquire(spec: Option<&LeaseSpec>, identity: &str, now: DateTime<Utc>) -> bool {
+    let Some(spec) = spec else {
+        return true;
+    };
+    match spec.holder_identity.as_deref() {
+        None | Some("") => true,
+        Some(holder) if holder == identity => true,
+        Some(_) => {
+            let duration = spec.lease_duration_seconds.unwrap_or_default();
+            spec.renew_time
+                .as_ref()
+                .is_none_or(|t| t.0 + Duration::from_secs(duration as u64) < now)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn held_by(holder: &str, renewed: DateTime<Utc>) -> LeaseSpec {
+        LeaseSpec {
+            holder_identity: Some(holder.to_string()),
+            lease_duration_seconds: Some(15),
+            renew_time: Some(MicroTime(renewed)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_can_acquire() {
+        let now = Utc::now();
+        assert!(can_acquire(None, "a", now));
+        assert!(can_acquire(Some(&LeaseSpec::default()), "a", now));
+        assert!(can_acquire(Some(&held_by("a", now)), "a", now));
+        assert!(!can_acquire(Some(&held_by("b", now)), "a", now));
+
+        let expired = now - Duration::from_secs(16);
+        assert!(can_acquire(Some(&held_by("b", expired)), "a", now));
+    }
+}
@@ -1,7 +1,13 @@
 // Copyright 2025, Jeroen van Erp <jeroen@geeko.me>
 // SPDX-License-Identifier: Apache-2.0
+pub mod config;
 pub mod controller;
 pub mod grower;
+pub mod health;
 pub mod kubernetes;
+pub mod leader;
+pub mod metrics;
+pub mod shutdown;
 pub mod sprout;
+pub mod types;
 pub mod utils;
@@ -1,8 +1,19 @@
 // Copyright 2025, Jeroen van Erp <jeroen@geeko.me>
 // SPDX-License-Identifier: Apache-2.0
-use tracing::info;
+use std::io::Write;
+use std::sync::Arc;
 
-use sprouter::controller::{configmap, namespace, secret};
+use tracing::{info, warn};
+
+use sprouter::config::Config;
+use sprouter::controller::{
+    clusterseed, configmap, gc, namespace, secret, sproutpolicy, sproutrequest,
+};
+use sprouter::health::{self, HEALTH};
+use sprouter::kubernetes::cache::{self, Cache};
+use sprouter::leader::LeaderElector;
+use sprouter::metrics::{self, METRICS};
+use sprouter::shutdown::{self, SHUTDOWN};
 use sprouter::sprout::manager::SproutManager;
 
 #[tokio::main]
@@ -10,18 +21,96 @@ async fn main() -> anyhow::Result<()> {
     tracing_subscriber::fmt::init();
     info!("Starting the Sprouter controller...");
 
+    let config = Config::from_env()?;
     let client = kube::Client::try_default().await?;
 
-    // Initialize the SproutManager
-    let sprout_manager = SproutManager::new(client.clone());
-    sprout_manager.init().await?;
-    info!("SproutManager initialized.");
+    let metrics_addr = config.metrics_addr;
+    let health_addr = config.health_addr;
+    let liveness_threshold = config.liveness_threshold;
+    let shutdown_timeout = config.shutdown_timeout;
+    let leader = config.leader_election.then(|| {
+        let identity = std::env::var("POD_NAME")
+            .or_else(|_| std::env::var("HOSTNAME"))
+            .unwrap_or_else(|_| "sprouter".to_string());
+        LeaderElector::new(client.clone(), &config.lease_name, &identity)
+    });
+    let (cache, reflectors) = Cache::new();
+    let sprout_manager = Arc::new(SproutManager::new(client.clone(), config, cache.clone()));
+
+    tokio::spawn(async {
+        shutdown::signal().await;
+        info!("Shutting down, draining in-flight propagation...");
+        SHUTDOWN.trigger();
+    });
+
+    let run = async {
+        if let Some(leader) = &leader {
+            // A standby replica is ready once its seeds, reports and caches are loaded, so it can
+            // take over without growing the sprouts that are already in sync
+            sprout_manager.warm().await?;
+            HEALTH.set_ready();
+            tokio::select! {
+                result = leader.acquire() => result?,
+                _ = SHUTDOWN.wait() => return Ok(()),
+            }
+        }
+
+        // Initialize the SproutManager
+        sprout_manager.init().await?;
+        HEALTH.set_ready();
+        info!("SproutManager initialized.");
+
+        // The watchers and controllers stop taking new events on shutdown, and return once the
+        // events in flight are fully handled
+        tokio::try_join!(
+            configmap::run(client.clone(), sprout_manager.clone()),
+            secret::run(client.clone(), sprout_manager.clone()),
+            configmap::run_sprouts(&cache, &sprout_manager),
+            secret::run_sprouts(&cache, &sprout_manager),
+            namespace::run(client.clone(), &sprout_manager),
+            sproutrequest::run(client.clone(), &sprout_manager),
+            sproutpolicy::run(client.clone(), &sprout_manager),
+            clusterseed::run(client.clone(), &sprout_manager),
+            gc::run(&sprout_manager),
+            async {
+                match &leader {
+                    Some(leader) => tokio::select! {
+                        result = leader.keep() => result,
+                        _ = SHUTDOWN.wait() => Ok(()),
+                    },
+                    None => Ok(()),
+                }
+            },
+        )?;
+        anyhow::Ok(())
+    };
 
-    tokio::try_join!(
-        configmap::run(client.clone(), &sprout_manager),
-        secret::run(client.clone(), &sprout_manager),
-        namespace::run(client.clone(), &sprout_manager),
-    )?;
+    // Metrics and health keep being served, and the caches kept up to date, while draining
+    let result = tokio::select! {
+        result = async {
+            tokio::try_join!(
+                metrics::serve(metrics_addr),
+                health::serve(health_addr, liveness_threshold),
+                cache::run(client.clone(), reflectors),
+            )
+        } => result.map(|_| ()),
+        result = run => result,
+        _ = async {
+            SHUTDOWN.wait().await;
+            tokio::time::sleep(shutdown_timeout).await;
+        } => {
+            warn!("In-flight propagation did not finish within {:?}", shutdown_timeout);
+            Ok(())
+        }
+    };
 
-    Ok(())
+    if let Some(leader) = &leader {
+        if let Err(e) = leader.release().await {
+            warn!("Releasing the lease failed: {}", e);
+        }
+    }
+    // Metrics are scraped, so log the final values that may not have been scraped yet
+    info!("Sprouter stopped, final metrics:\n{}", METRICS.render());
+    std::io::stdout().flush()?;
+    result
 }
@@ -0,0 +1,153 @@
+// Copyright 2025, Jeroen van Erp <jeroen@geeko.me>
+// SPDX-License-Identifier: Apache-2.0
+use std::net::SocketAddr;
+use std::sync::LazyLock;
+
+use anyhow::Result;
+use axum::Router;
+use axum::routing::get;
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounterVec, IntGaugeVec, Opts, Registry, TextEncoder,
+};
+use tracing::info;
+
+/// The metrics of the operator, served on `/metrics`.
+pub static METRICS: LazyLock<Metrics> = LazyLock::new(Metrics::new);
+
+pub struct Metrics {
+    registry: Registry,
+    /// Sprouts acted upon, by kind and action (created, updated, reverted, drifted, ignored, pruned,
+    /// deleted).
+    pub sprouts: IntCounterVec,
+    /// Known seeds, by kind.
+    pub seeds: IntGaugeVec,
+    /// Failures to grow, prune or delete a sprout, by target namespace.
+    pub sprout_errors: IntCounterVec,
+    /// Watchers that failed and had to restart, by watched resource.
+    pub watcher_restarts: IntCounterVec,
+    /// Watch events that failed to be handled and are retried, by watched resource.
+    pub reconcile_errors: IntCounterVec,
+    /// Time from observing a change to the data of a seed until all of its sprouts are synced, by
+    /// kind.
+    pub propagation_latency: HistogramVec,
+}
+
+impl Metrics {
+    fn new() -> Self {
+        let registry =
+            Registry::new_custom(Some("sprouter".to_string()), None).expect("valid metrics prefix");
+        let sprouts = IntCounterVec::new(
+            Opts::new("sprouts_total", "Sprouts acted upon"),
+            &["kind", "action"],
+        )
+        .expect("valid metric");
+        let seeds =
+            IntGaugeVec::new(Opts::new("seeds", "Known seeds"), &["kind"]).expect("valid metric");
+        let sprout_errors = IntCounterVec::new(
+            Opts::new(
+                "sprout_errors_total",
+                "Failures to grow, prune or delete a sprout",
+            ),
+            &["namespace"],
+        )
+        .expect("valid metric");
+        let watcher_restarts = IntCounterVec::new(
+            Opts::new(
+                "watcher_restarts_total",
+                "Watchers that failed and restarted",
+            ),
+            &["watcher"],
+        )
+        .expect("valid metric");
+        let reconcile_errors = IntCounterVec::new(
+            Opts::new(
+                "reconcile_errors_total",
+                "Watch events that failed to be handled and are retried",
+            ),
+            &["watcher"],
+        )
+        .expect("valid metric");
+        let propagation_latency = HistogramVec::new(
+            HistogramOpts::new(
+                "seed_propagation_latency_seconds",
+                "Time from observing a change to the data of a seed until all of its sprouts are synced",
+            )
+            .buckets(vec![0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0]),
+            &["kind"],
+        )
+        .expect("valid metric");
+
+        for collector in [
+            Box::new(sprouts.clone()) as Box<dyn prometheus::core::Collector>,
+            Box::new(seeds.clone()),
+            Box::new(sprout_errors.clone()),
+            Box::new(watcher_restarts.clone()),
+            Box::new(reconcile_errors.clone()),
+            Box::new(propagation_latency.clone()),
+        ] {
+            registry.register(collector).expect("unique metric");
+        }
+
+        Self {
+            registry,
+            sprouts,
+            seeds,
+            sprout_errors,
+            watcher_restarts,
+            reconcile_errors,
+            propagation_latency,
+        }
+    }
+
+    /// Counts `count` sprouts of the kind the action was taken on.
+    pub fn record_sprouts(&self, kind: impl ToString, action: &str, count: u64) {
+        self.sprouts
+            .with_label_values(&[&kind.to_string(), action])
+            .inc_by(count);
+    }
+
+    /// Counts a failure to grow, prune or delete a sprout in the namespace.
+    pub fn record_sprout_error(&self, namespace: &str) {
+        self.sprout_errors.with_label_values(&[namespace]).inc();
+    }
+
+    /// Counts a failure of the watcher of the given resource.
+    pub fn record_watcher_restart(&self, watcher: &str) {
+        self.watcher_restarts.with_label_values(&[watcher]).inc();
+    }
+
+    /// Counts a watch event of the given resource that failed to be handled.
+    pub fn record_reconcile_error(&self, watcher: &str) {
+        self.reconcile_errors.with_label_values(&[watcher]).inc();
+    }
+
+    /// The metrics in the Prometheus text format.
+    pub fn render(&self) -> String {
+        let mut buffer = Vec::new();
+        TextEncoder::new()
+            .encode(&self.registry.gather(), &mut buffer)
+            .expect("metrics are encodable");
+        String::from_utf8(buffer).expect("metrics are UTF-8")
+    }
+}
+
+/// Serves `/metrics` on the address until the process exits.
+pub async fn serve(addr: SocketAddr) -> Result<()> {
+    let app = Router::new().route("/metrics", get(|| async { METRICS.render() }));
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    info!("Serving metrics on {}", addr);
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_includes_recorded_sprouts() {
+        METRICS.record_sprouts("ConfigMap", "created", 2);
+        let rendered = METRICS.render();
+        assert!(rendered.contains(r#"sprouter_sprouts_total{action="created",kind="ConfigMap"}"#));
+    }
+}
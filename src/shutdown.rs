@@ -0,0 +1,64 @@
+// Copyright 2025, Jeroen van Erp <jeroen@geeko.me>
+// SPDX-License-Identifier: Apache-2.0
+use std::sync::LazyLock;
+
+use tokio::sync::watch;
+
+/// Signals the watchers to stop taking new events once the operator is shutting down.
+pub static SHUTDOWN: LazyLock<Shutdown> = LazyLock::new(Shutdown::default);
+
+pub struct Shutdown {
+    tx: watch::Sender<bool>,
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Self {
+            tx: watch::Sender::new(false),
+        }
+    }
+}
+
+impl Shutdown {
+    pub fn trigger(&self) {
+        self.tx.send_replace(true);
+    }
+
+    pub fn is_triggered(&self) -> bool {
+        *self.tx.borrow()
+    }
+
+    /// Completes once the shutdown is triggered, immediately if it already was.
+    pub async fn wait(&self) {
+        let mut rx = self.tx.subscribe();
+        // The sender lives as long as self, so this only returns once triggered
+        let _ = rx.wait_for(|triggered| *triggered).await;
+    }
+}
+
+/// Completes on SIGINT, or on the SIGTERM Kubernetes sends when stopping the pod.
+pub async fn signal() {
+    let mut sigterm = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+        .expect("SIGTERM handler can be installed");
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => {}
+        _ = sigterm.recv() => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_wait_completes_once_triggered() {
+        let shutdown = Shutdown::default();
+        assert!(!shutdown.is_triggered());
+        let waiting = tokio::time::timeout(std::time::Duration::from_millis(10), shutdown.wait());
+        assert!(waiting.await.is_err());
+
+        shutdown.trigger();
+        assert!(shutdown.is_triggered());
+        shutdown.wait().await;
+    }
+}
@@ -1,19 +1,43 @@
 // Copyright 2025, Jeroen van Erp <jeroen@geeko.me>
 // SPDX-License-Identifier: Apache-2.0
+use k8s_openapi::ByteString;
 use k8s_openapi::api::core::v1::{ConfigMap, Secret};
+use kube::ResourceExt;
+use kube::api::ObjectMeta;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use std::{collections::BTreeMap, fmt};
 
+use crate::kubernetes::cache::Cached;
+use crate::types::ClusterSeed;
 use crate::utils::hash_seed_data;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
 pub enum SproutKind {
     ConfigMap,
     Secret,
 }
 
-pub trait AsSproutKind {
+/// A kind of object that can be sprouted, all of which are cached.
+pub trait AsSproutKind: Cached {
     fn sprout_kind() -> SproutKind;
     fn hash(&self) -> Option<String>;
+    /// The object carrying the data of a ClusterSeed. It has no namespace, so it can be sprouted
+    /// into any namespace.
+    fn from_cluster_seed(seed: &ClusterSeed) -> Self;
+    /// An object without data, standing in for one of which only the metadata is known.
+    fn from_meta(metadata: ObjectMeta) -> Self;
+}
+
+fn cluster_seed_meta(seed: &ClusterSeed) -> ObjectMeta {
+    ObjectMeta {
+        name: Some(seed.name_any()),
+        ..Default::default()
+    }
+}
+
+fn non_empty<V>(data: BTreeMap<String, V>) -> Option<BTreeMap<String, V>> {
+    if data.is_empty() { None } else { Some(data) }
 }
 
 impl AsSproutKind for ConfigMap {
@@ -23,16 +47,16 @@ impl AsSproutKind for ConfigMap {
 
     fn hash(&self) -> Option<String> {
         let mut merged: BTreeMap<String, Vec<u8>> = BTreeMap::new();
-        self.data.as_ref().map(|data| {
+        if let Some(data) = self.data.as_ref() {
             for (k, v) in data {
                 merged.insert(k.clone(), v.as_bytes().to_vec());
             }
-        });
-        self.binary_data.as_ref().map(|data| {
+        }
+        if let Some(data) = self.binary_data.as_ref() {
             for (k, v) in data {
                 merged.insert(k.clone(), v.0.clone());
             }
-        });
+        }
 
         if merged.is_empty() {
             None
@@ -40,6 +64,22 @@ impl AsSproutKind for ConfigMap {
             Some(hash_seed_data(&merged))
         }
     }
+
+    fn from_cluster_seed(seed: &ClusterSeed) -> Self {
+        ConfigMap {
+            metadata: cluster_seed_meta(seed),
+            data: non_empty(seed.spec.data.clone()),
+            binary_data: non_empty(seed.spec.binary_data.clone()),
+            ..Default::default()
+        }
+    }
+
+    fn from_meta(metadata: ObjectMeta) -> Self {
+        ConfigMap {
+            metadata,
+            ..Default::default()
+        }
+    }
 }
 
 impl AsSproutKind for Secret {
@@ -48,11 +88,37 @@ impl AsSproutKind for Secret {
     }
 
     fn hash(&self) -> Option<String> {
-        self.data.as_ref().map(|data| {
-            let converted: BTreeMap<String, Vec<u8>> =
-                data.iter().map(|(k, v)| (k.clone(), v.0.clone())).collect();
-            hash_seed_data(&converted)
-        })
+        // Like for ConfigMaps, no data and empty data are the same
+        self.data
+            .as_ref()
+            .filter(|data| !data.is_empty())
+            .map(|data| {
+                let converted: BTreeMap<String, Vec<u8>> =
+                    data.iter().map(|(k, v)| (k.clone(), v.0.clone())).collect();
+                hash_seed_data(&converted)
+            })
+    }
+
+    fn from_cluster_seed(seed: &ClusterSeed) -> Self {
+        let data = seed
+            .spec
+            .data
+            .iter()
+            .map(|(k, v)| (k.clone(), ByteString(v.as_bytes().to_vec())))
+            .chain(seed.spec.binary_data.clone())
+            .collect();
+        Secret {
+            metadata: cluster_seed_meta(seed),
+            data: non_empty(data),
+            ..Default::default()
+        }
+    }
+
+    fn from_meta(metadata: ObjectMeta) -> Self {
+        Secret {
+            metadata,
+            ..Default::default()
+        }
     }
 }
 
@@ -0,0 +1,96 @@
+// Copyright 2025, Jeroen van Erp <jeroen@geeko.me>
+// SPDX-License-Identifier: Apache-2.0
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use crate::metrics::METRICS;
+
+/// The changes to seeds that their watchers saw, but that have not been propagated to all of
+/// their sprouts yet, to measure how long propagating a change takes.
+#[derive(Default)]
+pub struct PendingChanges {
+    changes: Mutex<HashMap<(String, String), PendingChange>>,
+}
+
+struct PendingChange {
+    observed: Instant,
+    /// Whether the data of the seed changed, known once propagating the change is first tried.
+    changed: Option<bool>,
+}
+
+impl PendingChanges {
+    /// Notes a change to the seed of `kind`, unless an earlier change is still being propagated.
+    pub fn observed(&self, kind: &str, seed: &str) {
+        self.changes
+            .lock()
+            .unwrap()
+            .entry((kind.to_string(), seed.to_string()))
+            .or_insert_with(|| PendingChange {
+                observed: Instant::now(),
+                changed: None,
+            });
+    }
+
+    /// Notes an attempt to propagate the pending change to the seed, if any, which `changed` its
+    /// data or not. Once all sprouts are `synced`, the latency of a change to the data is recorded.
+    pub fn propagated(&self, kind: &str, seed: &str, changed: bool, synced: bool) {
+        let key = (kind.to_string(), seed.to_string());
+        let mut changes = self.changes.lock().unwrap();
+        let Some(change) = changes.get_mut(&key) else {
+            return;
+        };
+        // A retry can no longer tell whether the data changed, as the new hash is known by then
+        let changed = *change.changed.get_or_insert(changed);
+        if !synced {
+            return;
+        }
+        let observed = changes.remove(&key).map(|c| c.observed);
+        if let Some(observed) = observed.filter(|_| changed) {
+            METRICS
+                .propagation_latency
+                .with_label_values(&[kind])
+                .observe(observed.elapsed().as_secs_f64());
+        }
+    }
+
+    /// Forgets the pending change to the seed, which needs no propagating.
+    pub fn forget(&self, kind: &str, seed: &str) {
+        self.changes
+            .lock()
+            .unwrap()
+            .remove(&(kind.to_string(), seed.to_string()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recorded(kind: &str) -> u64 {
+        METRICS
+            .propagation_latency
+            .with_label_values(&[kind])
+            .get_sample_count()
+    }
+
+    #[test]
+    fn test_only_propagated_data_changes_are_recorded() {
+        let changes = PendingChanges::default();
+        changes.observed("LatencyTest", "ns/a");
+        changes.propagated("LatencyTest", "ns/a", true, false);
+        assert_eq!(recorded("LatencyTest"), 0);
+        // The retry sees the new hash already, but the change was to the data
+        changes.propagated("LatencyTest", "ns/a", false, true);
+        assert_eq!(recorded("LatencyTest"), 1);
+
+        changes.observed("LatencyTest", "ns/b");
+        changes.propagated("LatencyTest", "ns/b", false, true);
+        // A resync has no pending change
+        changes.propagated("LatencyTest", "ns/c", true, true);
+        changes.observed("LatencyTest", "ns/d");
+        changes.forget("LatencyTest", "ns/d");
+        changes.propagated("LatencyTest", "ns/d", true, true);
+        assert_eq!(recorded("LatencyTest"), 1);
+    }
+}
@@ -0,0 +1,81 @@
+// Copyright 2025, Jeroen van Erp <jeroen@geeko.me>
+// SPDX-License-Identifier: Apache-2.0
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::OwnedMutexGuard;
+
+/// Serializes the work on each seed, so its sprouts are never written concurrently, while
+/// different seeds are worked on in parallel.
+#[derive(Default)]
+pub struct SeedLocks {
+    locks: Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>,
+}
+
+impl SeedLocks {
+    /// Waits until no one else works on the seed, given by its kind and `namespace/name`.
+    pub async fn lock(&self, kind: impl fmt::Display, seed: &str) -> SeedGuard<'_> {
+        let key = format!("{} {}", kind, seed);
+        let lock = self
+            .locks
+            .lock()
+            .unwrap()
+            .entry(key.clone())
+            .or_default()
+            .clone();
+        SeedGuard {
+            locks: self,
+            key,
+            guard: Some(lock.lock_owned().await),
+        }
+    }
+}
+
+/// Held while working on a seed. Forgets the lock of the seed once no one else waits for it.
+pub struct SeedGuard<'a> {
+    locks: &'a SeedLocks,
+    key: String,
+    guard: Option<OwnedMutexGuard<()>>,
+}
+
+impl Drop for SeedGuard<'_> {
+    fn drop(&mut self) {
+        self.guard.take();
+        let mut locks = self.locks.locks.lock().unwrap();
+        // Others clone the lock while holding the map, so nobody can be about to take it
+        if locks
+            .get(&self.key)
+            .is_some_and(|l| Arc::strong_count(l) == 1)
+        {
+            locks.remove(&self.key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_lock_serializes_per_seed() {
+        let locks = SeedLocks::default();
+        let guard = locks.lock("ConfigMap", "default/shared").await;
+
+        // Another seed is not blocked
+        drop(locks.lock("ConfigMap", "default/other").await);
+        drop(locks.lock("Secret", "default/shared").await);
+
+        let waiting = tokio::time::timeout(
+            Duration::from_secs(1),
+            locks.lock("ConfigMap", "default/shared"),
+        );
+        assert!(waiting.await.is_err());
+
+        drop(guard);
+        assert!(locks.locks.lock().unwrap().is_empty());
+        drop(locks.lock("ConfigMap", "default/shared").await);
+        assert!(locks.locks.lock().unwrap().is_empty());
+    }
+}
@@ -1,23 +1,49 @@
 // Copyright 2025, Jeroen van Erp <jeroen@geeko.me>
 // SPDX-License-Identifier: Apache-2.0
-use std::collections::HashSet;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
-use crate::utils::is_seed;
+use crate::utils::{is_seed, seed_ref, sprout_of, toggle_finalizer};
 use crate::{
-    grower::{delete_sprouts, grow_sprouts},
+    config::Config,
+    grower::{
+        delete_orphans, delete_sprouts, desired_namespaces, grow_sprout, grow_sprouts,
+        is_desired_in, prune_sprout, request_status, requests_for, update_request_status,
+    },
+    kubernetes::cache::{Cache, Cached},
+    kubernetes::events::{Events, Reason, SproutEvent},
     kubernetes::manager::{KubeResourceManager, ResourceManager},
-    sprout::kind::{AsSproutKind, SproutKind, kind_of},
+    kubernetes::throttle::Throttle,
+    metrics::METRICS,
+    sprout::kind::{AsSproutKind, SproutKind, infer_kind, kind_of},
+    sprout::latency::PendingChanges,
+    sprout::locks::{SeedGuard, SeedLocks},
+    sprout::policy::{ParsedPolicy, Propagation},
+    sprout::report::{changed_report, delete_report, load_report, save_report, with_sprout},
+    types::{
+        ClusterSeed, SproutInventory, SproutPolicy, SproutReport, SproutRequest,
+        SproutRequestStatus,
+    },
 };
-use anyhow::Result;
+use anyhow::{Result, bail};
 use k8s_openapi::{
     NamespaceResourceScope,
-    api::core::v1::{ConfigMap, Secret},
+    api::core::v1::{ConfigMap, Namespace, Secret},
+    chrono::{DateTime, Utc},
 };
 use kube::ResourceExt;
-use kube::{Api, Client, api::ListParams};
-use std::sync::Arc;
+use kube::core::PartialObjectMeta;
+use kube::{
+    Api, Client,
+    api::{ListParams, Patch, PatchParams},
+};
+use serde_json::json;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
 use tokio::sync::RwLock;
-use tracing::info;
+use tracing::{debug, info, warn};
+
+/// How far the clock of the API server may be behind that of the operator.
+const INIT_CLOCK_SKEW: Duration = Duration::from_secs(30);
 
 #[derive(Eq, Hash, PartialEq, Clone)]
 pub struct Seed {
@@ -26,22 +52,75 @@ pub struct Seed {
     resource_type: SproutKind,
 }
 
+/// Keeps track of the seeds and grows their sprouts. The registries are only locked to read or
+/// change them, never while sprouts are grown; instead, the work on each seed is serialized by
+/// `locks`.
 pub struct SproutManager {
     client: Client,
+    config: Config,
     seeds: Arc<RwLock<HashSet<Seed>>>,
+    policies: Arc<RwLock<Vec<ParsedPolicy>>>,
+    cluster_seeds: Arc<RwLock<BTreeMap<String, ClusterSeed>>>,
+    /// The hash of the data the sprouts of each seed should have, by kind and seed, to tell
+    /// whether a changed sprout drifted without looking up its seed.
+    sprout_hashes: RwLock<HashMap<(SproutKind, String), Option<String>>>,
+    /// The reports of the seeds as last saved, by kind and seed, so unchanged reports are not
+    /// saved again.
+    reports: RwLock<HashMap<(SproutKind, String), SproutReport>>,
+    /// The seeds whose report was loaded at startup, so their first reconcile can skip growing
+    /// sprouts that are in sync.
+    resumable: RwLock<HashSet<(SproutKind, String)>>,
+    /// The resource version of each seed as changed by setting its finalizer, so the seed
+    /// controllers do not reconcile it again for that change.
+    own_changes: Mutex<HashMap<(SproutKind, String), String>>,
+    pending: PendingChanges,
+    locks: SeedLocks,
+    throttle: Arc<Throttle>,
+    cache: Cache,
+    events: Events,
+    initialized_since: OnceLock<DateTime<Utc>>,
 }
 
 impl SproutManager {
-    pub fn new(client: Client) -> Self {
+    pub fn new(client: Client, config: Config, cache: Cache) -> Self {
+        let throttle = Arc::new(Throttle::new(config.qps, config.burst));
         Self {
+            events: Events::new(client.clone(), throttle.clone()),
+            throttle,
             client,
             seeds: Arc::new(RwLock::new(HashSet::new())),
+            policies: Arc::new(RwLock::new(Vec::new())),
+            cluster_seeds: Arc::new(RwLock::new(BTreeMap::new())),
+            sprout_hashes: RwLock::new(HashMap::new()),
+            reports: RwLock::new(HashMap::new()),
+            resumable: RwLock::new(HashSet::new()),
+            own_changes: Mutex::new(HashMap::new()),
+            pending: PendingChanges::default(),
+            locks: SeedLocks::default(),
+            cache,
+            config,
+            initialized_since: OnceLock::new(),
         }
     }
 
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// When `init` started; namespaces created since may have been missed by it.
+    pub fn initialized_since(&self) -> Option<DateTime<Utc>> {
+        self.initialized_since.get().copied()
+    }
+
+    /// Registers the seeds and collects orphaned sprouts. The sprouts of the seeds are grown by
+    /// the seed controllers, which skip those that are in sync. After taking over from another
+    /// replica the reports are loaded again, as they may have changed since `warm`.
     pub async fn init(&self) -> Result<()> {
-        self.init_seeds::<ConfigMap>().await?;
-        self.init_seeds::<Secret>().await?;
+        self.warm().await?;
+        // Seeds deleted while the operator was not running left their sprouts behind
+        if let Err(e) = self.collect_orphans().await {
+            warn!("Collecting orphaned sprouts failed: {}", e);
+        }
 
         let lock = self.seeds.read().await;
         info!("SproutManager initialized with {} seeds", lock.len());
@@ -49,7 +128,25 @@ impl SproutManager {
         Ok(())
     }
 
-    async fn init_seeds<K>(&self) -> Result<()>
+    /// Loads the SproutPolicies, the known seeds and their reports without growing any sprouts,
+    /// so a standby replica is ready to take over and only needs to grow the sprouts of seeds that
+    /// changed since.
+    pub async fn warm(&self) -> Result<()> {
+        // Namespaces created from now on may have been missed by the leader when it fails, and
+        // the clock of the API server, which sets their creation time, may be behind
+        let _ = self.initialized_since.set(Utc::now() - INIT_CLOCK_SKEW);
+        self.init_policies().await?;
+        self.warm_seeds::<ConfigMap>().await?;
+        self.warm_seeds::<Secret>().await?;
+        self.cache.wait_until_ready().await?;
+
+        let lock = self.seeds.read().await;
+        info!("SproutManager warmed with {} seeds", lock.len());
+
+        Ok(())
+    }
+
+    async fn warm_seeds<K>(&self) -> Result<()>
     where
         K: kube::Resource<Scope = NamespaceResourceScope>
             + Clone
@@ -62,16 +159,192 @@ impl SproutManager {
             + AsSproutKind,
         <K as kube::Resource>::DynamicType: Default,
     {
+        self.throttle.acquire().await;
         let api: Api<K> = Api::all(self.client.clone());
-        let pot_seeds = api.list(&ListParams::default()).await?;
-        for pot_seed in pot_seeds {
-            if is_seed(pot_seed.meta()) {
-                self.add_seed(pot_seed).await?;
+        let mut seeds = vec![];
+        for meta in api.list_metadata(&ListParams::default()).await? {
+            let pot_seed = K::from_meta(meta.metadata);
+            if self.is_seed(&pot_seed).await {
+                seeds.push(Seed {
+                    name: pot_seed.name_any(),
+                    namespace: pot_seed.namespace().unwrap_or_default(),
+                    resource_type: kind_of(&pot_seed),
+                });
+                if let Some(report) = self.report_of(&pot_seed).await? {
+                    let key = (kind_of(&pot_seed), seed_ref(&pot_seed));
+                    self.resumable.write().await.insert(key.clone());
+                    self.reports.write().await.insert(key, report);
+                }
             }
         }
+        let mut lock = self.seeds.write().await;
+        lock.extend(seeds);
+        count_seeds(&lock);
         Ok(())
     }
 
+    /// Deletes the sprouts of seeds that no longer exist or are no longer a seed, or only reports
+    /// them in dry-run. Sprouts are found by their annotation, so sprouts of earlier versions that
+    /// lack the sprout label are collected as well.
+    pub async fn collect_orphans(&self) -> Result<()> {
+        let left = self.collect_orphans_of::<ConfigMap>().await?
+            + self.collect_orphans_of::<Secret>().await?;
+        if left > 0 && !self.config.gc_dry_run {
+            bail!("{} orphaned sprouts could not be deleted", left);
+        }
+        Ok(())
+    }
+
+    async fn collect_orphans_of<K>(&self) -> Result<usize>
+    where
+        K: kube::Resource<Scope = NamespaceResourceScope>
+            + Clone
+            + serde::de::DeserializeOwned
+            + serde::Serialize
+            + Sync
+            + Send
+            + std::fmt::Debug
+            + 'static
+            + AsSproutKind,
+        <K as kube::Resource>::DynamicType: Default,
+    {
+        self.throttle.acquire().await;
+        let api: Api<K> = Api::all(self.client.clone());
+        let mut sprouts: BTreeMap<String, Vec<K>> = BTreeMap::new();
+        for meta in api.list_metadata(&ListParams::default()).await? {
+            let sprout = K::from_meta(meta.metadata);
+            if let Some(seed) = sprout_of(sprout.meta()).cloned() {
+                sprouts.entry(seed).or_default().push(sprout);
+            }
+        }
+        let mgr = self.resource_manager::<K>();
+        let mut left = 0;
+        for (seed, orphans) in sprouts {
+            let _guard = self.lock_seed::<K>(&seed).await;
+            if self.seed_exists::<K>(&seed).await? {
+                continue;
+            }
+            left += delete_orphans(&seed, &orphans, self.config.gc_dry_run, &mgr).await;
+        }
+        Ok(left)
+    }
+
+    /// Whether the seed of kind `K`, given as `namespace/name` or as the name of a ClusterSeed,
+    /// exists and is a seed. ClusterSeeds are looked up, as they may not be registered yet.
+    async fn seed_exists<K>(&self, seed: &str) -> Result<bool>
+    where
+        K: kube::Resource<Scope = NamespaceResourceScope>
+            + Clone
+            + serde::de::DeserializeOwned
+            + serde::Serialize
+            + Sync
+            + Send
+            + std::fmt::Debug
+            + 'static
+            + AsSproutKind,
+        <K as kube::Resource>::DynamicType: Default,
+    {
+        match seed.split_once('/') {
+            Some((namespace, name)) => {
+                self.throttle.acquire().await;
+                let api: Api<K> = Api::namespaced(self.client.clone(), namespace);
+                match api.get_metadata_opt(name).await? {
+                    Some(meta) => Ok(self.is_seed(&K::from_meta(meta.metadata)).await),
+                    None => Ok(false),
+                }
+            }
+            None => {
+                self.throttle.acquire().await;
+                let api: Api<ClusterSeed> = Api::all(self.client.clone());
+                let cluster_seed = api.get_opt(seed).await?;
+                Ok(cluster_seed.is_some_and(|s| s.spec.kind == infer_kind::<K>()))
+            }
+        }
+    }
+
+    /// Grows the sprouts of a seed like `add_seed`, but the first time after startup skips those
+    /// the report loaded by `warm` shows were grown from its current data and propagation.
+    pub async fn resume_seed<K>(&self, resource: K) -> Result<()>
+    where
+        K: kube::Resource<Scope = NamespaceResourceScope>
+            + Clone
+            + serde::de::DeserializeOwned
+            + serde::Serialize
+            + Sync
+            + Send
+            + std::fmt::Debug
+            + 'static
+            + AsSproutKind,
+        <K as kube::Resource>::DynamicType: Default,
+    {
+        let key = (kind_of(&resource), seed_ref(&resource));
+        let report = if self.resumable.write().await.remove(&key) {
+            self.reports.read().await.get(&key).cloned()
+        } else {
+            None
+        };
+        let Some(report) = report.filter(is_synced) else {
+            return self.add_seed(resource).await;
+        };
+        if resource.meta().deletion_timestamp.is_some() {
+            return self.add_seed(resource).await;
+        }
+        {
+            let _guard = self.locks.lock(key.0, &key.1).await;
+            let Some(propagation) = self.propagation_of(&resource).await else {
+                return self.add_seed(resource).await;
+            };
+            let mgr = self.resource_manager::<K>();
+            let (_, hash) = propagation.sprout(resource.clone());
+            let requests = requests_for::<K, _>(&key.1, &mgr).await?;
+            let namespace = resource.namespace().unwrap_or_default();
+            let desired = desired_namespaces(&namespace, &requests, &propagation, &mgr).await?;
+            let grown: BTreeSet<String> =
+                report.sprouts.iter().map(|s| s.namespace.clone()).collect();
+            if report.seed_hash == hash && grown == desired {
+                debug!("Sprouts of '{}' are in sync, not growing them", key.1);
+                self.pending.forget(&key.0.to_string(), &key.1);
+                self.set_finalizer(&resource, true).await?;
+                {
+                    let mut lock = self.seeds.write().await;
+                    lock.insert(Seed {
+                        name: resource.name_any(),
+                        namespace,
+                        resource_type: kind_of(&resource),
+                    });
+                    count_seeds(&lock);
+                }
+                self.sprout_hashes
+                    .write()
+                    .await
+                    .insert(key, resource.hash());
+                return Ok(());
+            }
+        }
+        self.add_seed(resource).await
+    }
+
+    /// The full object of a seed of which only the metadata is known, unless it was deleted in
+    /// the meantime.
+    pub async fn fetch_seed<K>(&self, seed: &K) -> Result<Option<K>>
+    where
+        K: kube::Resource<Scope = NamespaceResourceScope>
+            + Clone
+            + serde::de::DeserializeOwned
+            + serde::Serialize
+            + Sync
+            + Send
+            + std::fmt::Debug
+            + 'static
+            + AsSproutKind,
+        <K as kube::Resource>::DynamicType: Default,
+    {
+        let namespace = seed.namespace().unwrap_or_default();
+        self.throttle.acquire().await;
+        let api: Api<K> = Api::namespaced(self.client.clone(), &namespace);
+        Ok(api.get_opt(&seed.name_any()).await?)
+    }
+
     pub async fn add_seed<K>(&self, resource: K) -> Result<()>
     where
         K: kube::Resource<Scope = NamespaceResourceScope>
@@ -85,19 +358,55 @@ impl SproutManager {
             + AsSproutKind,
         <K as kube::Resource>::DynamicType: Default,
     {
+        // A seed that is being deleted is only kept around by the finalizer, until its sprouts
+        // are deleted
+        if resource.meta().deletion_timestamp.is_some() {
+            return self.delete_seed(resource).await;
+        }
         info!(
             "Growing sprouts for '{}/{}'",
             resource.namespace().unwrap_or_default(),
             resource.name_any()
         );
-        let mut lock: tokio::sync::RwLockWriteGuard<'_, HashSet<Seed>> = self.seeds.write().await;
-        lock.insert(Seed {
-            name: resource.name_any(),
-            namespace: resource.namespace().unwrap_or_default(),
-            resource_type: kind_of(&resource),
-        });
-        let mgr = KubeResourceManager::<K>::new(self.client.clone());
-        grow_sprouts(resource.clone(), &mgr).await?;
+        let kind = kind_of(&resource).to_string();
+        let _guard = self
+            .locks
+            .lock(kind_of(&resource), &seed_ref(&resource))
+            .await;
+        self.set_finalizer(&resource, true).await?;
+        {
+            let mut lock = self.seeds.write().await;
+            lock.insert(Seed {
+                name: resource.name_any(),
+                namespace: resource.namespace().unwrap_or_default(),
+                resource_type: kind_of(&resource),
+            });
+            count_seeds(&lock);
+        }
+        let Some(propagation) = self.propagation_of(&resource).await else {
+            self.pending.forget(&kind, &seed_ref(&resource));
+            return Ok(());
+        };
+        let previous = self
+            .sprout_hashes
+            .write()
+            .await
+            .insert((kind_of(&resource), seed_ref(&resource)), resource.hash());
+        let changed = previous != Some(resource.hash());
+        let mgr = self.resource_manager::<K>();
+        let (report, failed) = grow_sprouts(
+            resource.clone(),
+            &mgr,
+            &propagation,
+            self.config.concurrency,
+        )
+        .await?;
+        self.save_report(&resource, &report).await;
+        let seed = seed_ref(&resource);
+        self.pending.propagated(&kind, &seed, changed, failed == 0);
+        if failed > 0 {
+            bail!("{} sprouts of '{}' failed", failed, seed_ref(&resource));
+        }
         Ok(())
     }
 
@@ -119,15 +428,179 @@ impl SproutManager {
             resource.namespace().unwrap_or_default(),
             resource.name_any()
         );
-        let mut lock = self.seeds.write().await;
-        lock.remove(&Seed {
-            name: resource.name_any(),
-            namespace: resource.namespace().unwrap_or_default(),
-            resource_type: kind_of(&resource),
+        let _guard = self
+            .locks
+            .lock(kind_of(&resource), &seed_ref(&resource))
+            .await;
+        let mgr = self.resource_manager::<K>();
+        // The seed stays known until all of its sprouts are gone, so the deletion can be retried
+        delete_sprouts(resource.clone(), &mgr, self.config.concurrency).await?;
+        {
+            let mut lock = self.seeds.write().await;
+            lock.remove(&Seed {
+                name: resource.name_any(),
+                namespace: resource.namespace().unwrap_or_default(),
+                resource_type: kind_of(&resource),
+            });
+            count_seeds(&lock);
+        }
+        self.sprout_hashes
+            .write()
+            .await
+            .remove(&(kind_of(&resource), seed_ref(&resource)));
+        self.reports
+            .write()
+            .await
+            .remove(&(kind_of(&resource), seed_ref(&resource)));
+        self.pending
+            .forget(&kind_of(&resource).to_string(), &seed_ref(&resource));
+        if let Err(e) = delete_report(&self.client, &self.throttle, &resource).await {
+            warn!(
+                "Deleting the report of '{}' failed: {}",
+                seed_ref(&resource),
+                e
+            );
+        }
+        self.set_finalizer(&resource, false).await
+    }
+
+    /// Adds or removes the finalizer that keeps a seed around until its sprouts are deleted.
+    async fn set_finalizer<K>(&self, seed: &K, present: bool) -> Result<()>
+    where
+        K: kube::Resource<Scope = NamespaceResourceScope>
+            + Clone
+            + serde::de::DeserializeOwned
+            + serde::Serialize
+            + Sync
+            + Send
+            + std::fmt::Debug
+            + 'static
+            + AsSproutKind,
+        <K as kube::Resource>::DynamicType: Default,
+    {
+        let Some(finalizers) = toggle_finalizer(seed.meta(), present) else {
+            return Ok(());
+        };
+        let namespace = seed.namespace().unwrap_or_default();
+        self.throttle.acquire().await;
+        let api: Api<K> = Api::namespaced(self.client.clone(), &namespace);
+        // The resource version makes the patch fail if the finalizers changed in the meantime
+        let patch = json!({
+            "metadata": {
+                "finalizers": finalizers,
+                "resourceVersion": seed.resource_version(),
+            }
         });
-        let mgr = KubeResourceManager::<K>::new(self.client.clone());
-        delete_sprouts(resource.clone(), &mgr).await?;
-        Ok(())
+        let params = PatchParams::default();
+        match api
+            .patch_metadata(&seed.name_any(), &params, &Patch::Merge(&patch))
+            .await
+        {
+            Ok(patched) => {
+                if let Some(version) = patched.resource_version() {
+                    let key = (kind_of(seed), seed_ref(seed));
+                    self.own_changes.lock().unwrap().insert(key, version);
+                }
+                Ok(())
+            }
+            // A seed that is gone needs no finalizer removed
+            Err(kube::Error::Api(err)) if err.code == 404 && !present => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Notes that the watcher of seeds of `kind` saw a change to the seed, given as
+    /// `namespace/name` or the name of a ClusterSeed, to measure how long propagating it takes.
+    pub fn change_observed(&self, kind: &str, seed: &str) {
+        self.pending.observed(kind, seed);
+    }
+
+    /// Whether the seed is as changed by setting its finalizer, which needs no reconcile.
+    pub fn is_own_change<K>(&self, seed: &PartialObjectMeta<K>) -> bool
+    where
+        K: kube::Resource + AsSproutKind,
+    {
+        let key = (K::sprout_kind(), seed_ref(seed));
+        let mut own_changes = self.own_changes.lock().unwrap();
+        match own_changes.get(&key) {
+            Some(version) if seed.resource_version().as_ref() == Some(version) => {
+                own_changes.remove(&key);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether the resource is a seed, either by annotation or because a SproutPolicy selects it.
+    pub async fn is_seed<K>(&self, resource: &K) -> bool
+    where
+        K: kube::Resource<Scope = NamespaceResourceScope>
+            + Clone
+            + serde::de::DeserializeOwned
+            + serde::Serialize
+            + Sync
+            + Send
+            + std::fmt::Debug
+            + 'static
+            + AsSproutKind,
+        <K as kube::Resource>::DynamicType: Default,
+    {
+        if is_seed(resource.meta()) {
+            return true;
+        }
+        let policies = self.policies.read().await;
+        policies
+            .iter()
+            .any(|p| p.selects(kind_of(resource), resource.meta()))
+    }
+
+    /// The propagation of a seed, or `None` (with a warning) if it is invalid. Annotations on the
+    /// seed take precedence over SproutPolicies; if multiple policies select the seed, the first
+    /// one by name wins.
+    async fn propagation_of<K>(&self, resource: &K) -> Option<Propagation>
+    where
+        K: kube::Resource<Scope = NamespaceResourceScope>
+            + Clone
+            + serde::de::DeserializeOwned
+            + serde::Serialize
+            + Sync
+            + Send
+            + std::fmt::Debug
+            + 'static
+            + AsSproutKind,
+        <K as kube::Resource>::DynamicType: Default,
+    {
+        let propagation = if is_seed(resource.meta()) {
+            Propagation::from_annotations(resource.meta(), K::sprout_kind(), &self.config)
+        } else {
+            let policies = self.policies.read().await;
+            let policy = policies
+                .iter()
+                .find(|p| p.selects(kind_of(resource), resource.meta()))?;
+            Propagation::from_policy(
+                resource.meta(),
+                K::sprout_kind(),
+                &policy.policy,
+                &self.config,
+            )
+        };
+        match propagation {
+            Ok(propagation) => {
+                self.events.recovered::<K>(&seed_ref(resource), None);
+                Some(propagation)
+            }
+            Err(e) => {
+                warn!(
+                    "Seed '{}/{}' has an invalid propagation: {}",
+                    resource.namespace().unwrap_or_default(),
+                    resource.name_any(),
+                    e
+                );
+                let event = SproutEvent::new(Reason::InvalidPropagation, None, e.to_string());
+                self.events.publish::<K>(&seed_ref(resource), &event).await;
+                None
+            }
+        }
     }
 
     pub async fn is_known_seed<K>(&self, resource: K) -> bool
@@ -151,40 +624,1132 @@ impl SproutManager {
         })
     }
 
-    pub async fn new_namespace(&self, namespace: &str) -> Result<()> {
-        let lock = self.seeds.read().await;
+    pub async fn new_namespace(&self, ns: &Namespace) -> Result<()> {
+        self.sync_namespace(ns, false).await?;
+        info!(
+            "All known seeds sprouted in new namespace '{}'",
+            ns.name_any()
+        );
+        Ok(())
+    }
 
-        for seed in lock.iter() {
+    /// Re-evaluates all known seeds against a namespace whose labels or annotations changed,
+    /// growing sprouts of seeds that now match and pruning those of seeds that no longer do.
+    pub async fn namespace_changed(&self, ns: &Namespace) -> Result<()> {
+        self.sync_namespace(ns, true).await?;
+        info!(
+            "All known seeds re-evaluated for namespace '{}'",
+            ns.name_any()
+        );
+        Ok(())
+    }
+
+    /// Syncs every known seed into the namespace. A seed that fails does not keep the others
+    /// from syncing, but fails the namespace as a whole so it can be retried.
+    async fn sync_namespace(&self, ns: &Namespace, prune: bool) -> Result<()> {
+        let namespace = ns.name_any();
+        let mut failed = 0;
+        let seeds: Vec<Seed> = self.seeds.read().await.iter().cloned().collect();
+
+        for seed in &seeds {
             if seed.namespace == namespace {
                 continue;
             }
-            info!(
-                "Growing sprout of {} '{}/{}' to '{}/{}",
-                seed.resource_type, seed.namespace, seed.name, namespace, seed.name
-            );
-            match seed.resource_type {
+            let result = match seed.resource_type {
+                SproutKind::ConfigMap => self.sync_in_namespace::<ConfigMap>(seed, ns, prune).await,
+                SproutKind::Secret => self.sync_in_namespace::<Secret>(seed, ns, prune).await,
+            };
+            if let Err(e) = result {
+                warn!(
+                    "Syncing '{}/{}' into namespace '{}' failed: {}",
+                    seed.namespace, seed.name, namespace, e
+                );
+                failed += 1;
+            }
+        }
+
+        let cluster_seeds: Vec<ClusterSeed> =
+            self.cluster_seeds.read().await.values().cloned().collect();
+        for seed in &cluster_seeds {
+            let result = match seed.spec.kind {
                 SproutKind::ConfigMap => {
-                    let cm_api: Api<ConfigMap> =
-                        Api::namespaced(self.client.clone(), &seed.namespace);
-                    let cm = cm_api.get(&seed.name).await?;
-                    let mgr = KubeResourceManager::<ConfigMap>::new(self.client.clone());
-                    let h = &cm.hash();
-                    let sprout = crate::utils::create_sprout(cm, h);
-                    mgr.create_in_namespace(namespace, &sprout).await?;
+                    self.sync_cluster_seed_in_namespace::<ConfigMap>(seed, ns, prune)
+                        .await
                 }
                 SproutKind::Secret => {
-                    let sec_api: Api<Secret> =
-                        Api::namespaced(self.client.clone(), &seed.namespace);
-                    let sec = sec_api.get(&seed.name).await?;
-                    let mgr = KubeResourceManager::<Secret>::new(self.client.clone());
-                    let h = &sec.hash();
-                    let sprout = crate::utils::create_sprout(sec, h);
-                    mgr.create_in_namespace(namespace, &sprout).await?;
+                    self.sync_cluster_seed_in_namespace::<Secret>(seed, ns, prune)
+                        .await
+                }
+            };
+            if let Err(e) = result {
+                warn!(
+                    "Syncing ClusterSeed '{}' into namespace '{}' failed: {}",
+                    seed.name_any(),
+                    namespace,
+                    e
+                );
+                failed += 1;
+            }
+        }
+
+        if failed > 0 {
+            bail!(
+                "{} seeds failed to sync into namespace '{}'",
+                failed,
+                namespace
+            );
+        }
+        Ok(())
+    }
+
+    async fn sync_in_namespace<K>(&self, seed: &Seed, ns: &Namespace, prune: bool) -> Result<()>
+    where
+        K: kube::Resource<Scope = NamespaceResourceScope>
+            + Clone
+            + serde::de::DeserializeOwned
+            + serde::Serialize
+            + Sync
+            + Send
+            + std::fmt::Debug
+            + 'static
+            + AsSproutKind,
+        <K as kube::Resource>::DynamicType: Default,
+    {
+        let seed_ref = format!("{}/{}", seed.namespace, seed.name);
+        let _guard = self.locks.lock(seed.resource_type, &seed_ref).await;
+        self.throttle.acquire().await;
+        let api: Api<K> = Api::namespaced(self.client.clone(), &seed.namespace);
+        // The seed may be deleted while its deletion is still being handled
+        let Some(resource) = api.get_opt(&seed.name).await? else {
+            return Ok(());
+        };
+        let Some(propagation) = self.propagation_of(&resource).await else {
+            return Ok(());
+        };
+        self.sprout_in_namespace(resource, &propagation, ns, prune)
+            .await
+    }
+
+    async fn sync_cluster_seed_in_namespace<K>(
+        &self,
+        seed: &ClusterSeed,
+        ns: &Namespace,
+        prune: bool,
+    ) -> Result<()>
+    where
+        K: kube::Resource<Scope = NamespaceResourceScope>
+            + Clone
+            + serde::de::DeserializeOwned
+            + serde::Serialize
+            + Sync
+            + Send
+            + std::fmt::Debug
+            + 'static
+            + AsSproutKind,
+        <K as kube::Resource>::DynamicType: Default,
+    {
+        let _guard = self.locks.lock("ClusterSeed", &seed.name_any()).await;
+        // The ClusterSeed may have changed or been deleted while waiting for its lock, in which
+        // case its sprouts were just grown or deleted
+        let current = self
+            .cluster_seeds
+            .read()
+            .await
+            .get(&seed.name_any())
+            .cloned();
+        let Some(seed) = current.filter(|s| s.spec.kind == seed.spec.kind) else {
+            return Ok(());
+        };
+        let Some(propagation) = self.cluster_propagation_of(&seed).await else {
+            return Ok(());
+        };
+        self.sprout_in_namespace(K::from_cluster_seed(&seed), &propagation, ns, prune)
+            .await
+    }
+
+    /// Grows the sprout of a seed in the namespace if it is targeted or requested there, or prunes
+    /// it otherwise.
+    async fn sprout_in_namespace<K>(
+        &self,
+        resource: K,
+        propagation: &Propagation,
+        ns: &Namespace,
+        prune: bool,
+    ) -> Result<()>
+    where
+        K: kube::Resource<Scope = NamespaceResourceScope>
+            + Clone
+            + serde::de::DeserializeOwned
+            + serde::Serialize
+            + Sync
+            + Send
+            + std::fmt::Debug
+            + 'static
+            + AsSproutKind,
+        <K as kube::Resource>::DynamicType: Default,
+    {
+        let namespace = ns.name_any();
+        let name = resource.name_any();
+        let seed = seed_ref(&resource);
+        let mgr = self.resource_manager::<K>();
+        if is_desired_in::<K, _>(&seed, ns, propagation, &mgr).await? {
+            info!(
+                "Growing sprout of {} '{}' to '{}/{}",
+                infer_kind::<K>(),
+                seed,
+                namespace,
+                name
+            );
+            let (sprout, h) = propagation.sprout(resource.clone());
+            let current = mgr.get_in_namespace(&namespace, &name).await?;
+            let (_, entry) =
+                grow_sprout(&sprout, &h, &namespace, current, propagation, &mgr).await?;
+            self.update_report(&resource, &namespace, Some(entry)).await;
+        } else if prune {
+            prune_sprout(&seed, &name, &namespace, &mgr).await?;
+            self.update_report(&resource, &namespace, None).await;
+        }
+        Ok(())
+    }
+
+    /// Checks a sprout that was created or changed against its seed, and reverts or flags it if
+    /// its data drifted from the seed. Sprouts of seeds that are not known are left alone.
+    pub async fn sprout_changed<K>(&self, sprout: K) -> Result<()>
+    where
+        K: kube::Resource<Scope = NamespaceResourceScope>
+            + Clone
+            + serde::de::DeserializeOwned
+            + serde::Serialize
+            + Sync
+            + Send
+            + std::fmt::Debug
+            + 'static
+            + AsSproutKind,
+        <K as kube::Resource>::DynamicType: Default,
+    {
+        let Some(seed) = sprout_of(sprout.meta()).cloned() else {
+            return Ok(());
+        };
+        let expected = self
+            .sprout_hashes
+            .read()
+            .await
+            .get(&(infer_kind::<K>(), seed.clone()))
+            .cloned();
+        if expected.is_none_or(|hash| hash == sprout.hash()) {
+            return Ok(());
+        }
+        let _guard = self.lock_seed::<K>(&seed).await;
+        let Some((resource, propagation)) = self.resolve_seed::<K>(&seed).await? else {
+            return Ok(());
+        };
+        let namespace = sprout.namespace().unwrap_or_default();
+        let mgr = self.resource_manager::<K>();
+        let (desired, h) = propagation.sprout(resource.clone());
+        let (_, entry) =
+            grow_sprout(&desired, &h, &namespace, Some(sprout), &propagation, &mgr).await?;
+        self.update_report(&resource, &namespace, Some(entry)).await;
+        Ok(())
+    }
+
+    /// Regrows a sprout that was deleted by someone else, in its namespace only, as long as its
+    /// seed still targets the namespace or the sprout was requested there. Sprouts of seeds that
+    /// are not known are left alone.
+    pub async fn sprout_deleted<K>(&self, sprout: K) -> Result<()>
+    where
+        K: kube::Resource<Scope = NamespaceResourceScope>
+            + Clone
+            + serde::de::DeserializeOwned
+            + serde::Serialize
+            + Sync
+            + Send
+            + std::fmt::Debug
+            + 'static
+            + AsSproutKind,
+        <K as kube::Resource>::DynamicType: Default,
+    {
+        let Some(seed) = sprout_of(sprout.meta()).cloned() else {
+            return Ok(());
+        };
+        let known = self
+            .sprout_hashes
+            .read()
+            .await
+            .contains_key(&(infer_kind::<K>(), seed.clone()));
+        if !known {
+            return Ok(());
+        }
+        // Deleting or pruning the sprouts of a seed holds its lock, so once it is acquired the
+        // seed is either gone or no longer targets the namespace
+        let _guard = self.lock_seed::<K>(&seed).await;
+        let Some((resource, propagation)) = self.resolve_seed::<K>(&seed).await? else {
+            return Ok(());
+        };
+        let namespace = sprout.namespace().unwrap_or_default();
+        self.throttle.acquire().await;
+        let ns_api: Api<Namespace> = Api::all(self.client.clone());
+        let Some(ns) = ns_api.get_opt(&namespace).await? else {
+            return Ok(());
+        };
+        // The sprouts of a namespace that is being deleted go with it
+        if ns.metadata.deletion_timestamp.is_some() {
+            return Ok(());
+        }
+        let mgr = self.resource_manager::<K>();
+        if !is_desired_in::<K, _>(&seed, &ns, &propagation, &mgr).await? {
+            return Ok(());
+        }
+        info!(
+            "Regrowing deleted sprout of {} '{}' in '{}'",
+            infer_kind::<K>(),
+            seed,
+            namespace
+        );
+        // The cache may still hold the deleted sprout, so look it up on the API server
+        self.throttle.acquire().await;
+        let api: Api<K> = Api::namespaced(self.client.clone(), &namespace);
+        let current = api.get_opt(&sprout.name_any()).await?;
+        let (desired, h) = propagation.sprout(resource.clone());
+        let (_, entry) = grow_sprout(&desired, &h, &namespace, current, &propagation, &mgr).await?;
+        self.update_report(&resource, &namespace, Some(entry)).await;
+        Ok(())
+    }
+
+    /// Serializes the work on the seed of kind `K` given as `namespace/name`, or on the
+    /// ClusterSeed given by its name.
+    async fn lock_seed<K: AsSproutKind>(&self, seed: &str) -> SeedGuard<'_> {
+        if seed.contains('/') {
+            self.locks.lock(infer_kind::<K>(), seed).await
+        } else {
+            self.locks.lock("ClusterSeed", seed).await
+        }
+    }
+
+    /// The object a seed, given as `namespace/name` or as the name of a ClusterSeed, sprouts as
+    /// together with its propagation, unless it is no longer a seed of kind `K` or is being
+    /// deleted, which deletes its sprouts.
+    async fn resolve_seed<K>(&self, seed: &str) -> Result<Option<(K, Propagation)>>
+    where
+        K: kube::Resource<Scope = NamespaceResourceScope>
+            + Clone
+            + serde::de::DeserializeOwned
+            + serde::Serialize
+            + Sync
+            + Send
+            + std::fmt::Debug
+            + 'static
+            + AsSproutKind,
+        <K as kube::Resource>::DynamicType: Default,
+    {
+        match seed.split_once('/') {
+            Some((namespace, name)) => {
+                self.throttle.acquire().await;
+                let api: Api<K> = Api::namespaced(self.client.clone(), namespace);
+                let Some(resource) = api.get_opt(name).await? else {
+                    return Ok(None);
+                };
+                if resource.meta().deletion_timestamp.is_some() || !self.is_seed(&resource).await {
+                    return Ok(None);
+                }
+                let propagation = self.propagation_of(&resource).await;
+                Ok(propagation.map(|p| (resource, p)))
+            }
+            None => {
+                let cluster_seed = self.cluster_seeds.read().await.get(seed).cloned();
+                let Some(cluster_seed) = cluster_seed.filter(|s| s.spec.kind == infer_kind::<K>())
+                else {
+                    return Ok(None);
+                };
+                let propagation = self.cluster_propagation_of(&cluster_seed).await;
+                Ok(propagation.map(|p| (K::from_cluster_seed(&cluster_seed), p)))
+            }
+        }
+    }
+
+    /// Grows the sprout asked for by a SproutRequest and reports the outcome in its status.
+    pub async fn request_sprout(&self, request: &SproutRequest) -> Result<()> {
+        match request.spec.kind {
+            SproutKind::ConfigMap => self.fulfil_request::<ConfigMap>(request).await,
+            SproutKind::Secret => self.fulfil_request::<Secret>(request).await,
+        }
+    }
+
+    /// Prunes the sprout of a deleted SproutRequest, unless the seed targets the namespace anyway.
+    pub async fn withdraw_request(&self, request: &SproutRequest) -> Result<()> {
+        match request.spec.kind {
+            SproutKind::ConfigMap => self.withdraw::<ConfigMap>(request).await,
+            SproutKind::Secret => self.withdraw::<Secret>(request).await,
+        }
+    }
+
+    async fn fulfil_request<K>(&self, request: &SproutRequest) -> Result<()>
+    where
+        K: kube::Resource<Scope = NamespaceResourceScope>
+            + Clone
+            + serde::de::DeserializeOwned
+            + serde::Serialize
+            + Sync
+            + Send
+            + std::fmt::Debug
+            + 'static
+            + AsSproutKind,
+        <K as kube::Resource>::DynamicType: Default,
+    {
+        let _guard = self.locks.lock(infer_kind::<K>(), &request.spec.seed).await;
+        let namespace = request.namespace().unwrap_or_default();
+        let mgr = self.resource_manager::<K>();
+        let seed = self.requested_seed::<K>(request).await?;
+        let propagation = match &seed {
+            Some(seed) => self.propagation_of(seed).await,
+            None => None,
+        };
+        let status = match (seed, propagation) {
+            (Some(seed), Some(propagation)) => {
+                if seed.namespace().as_deref() == Some(namespace.as_str()) {
+                    request_status(&request.spec.seed, true, None, &None)
+                } else if !propagation.requestable {
+                    request_status(&request.spec.seed, false, None, &None)
+                } else {
+                    info!(
+                        "Growing requested sprout of {} '{}' to '{}'",
+                        request.spec.kind, request.spec.seed, namespace
+                    );
+                    let (sprout, h) = propagation.sprout(seed.clone());
+                    let current = mgr.get_in_namespace(&namespace, &sprout.name_any()).await?;
+                    let (growth, entry) =
+                        grow_sprout(&sprout, &h, &namespace, current, &propagation, &mgr).await?;
+                    self.update_report(&seed, &namespace, Some(entry)).await;
+                    request_status(&request.spec.seed, true, Some(growth), &h)
+                }
+            }
+            _ => SproutRequestStatus::failed(format!(
+                "{} '{}' is not a seed",
+                request.spec.kind, request.spec.seed
+            )),
+        };
+        update_request_status(request, status, &mgr).await
+    }
+
+    async fn withdraw<K>(&self, request: &SproutRequest) -> Result<()>
+    where
+        K: kube::Resource<Scope = NamespaceResourceScope>
+            + Clone
+            + serde::de::DeserializeOwned
+            + serde::Serialize
+            + Sync
+            + Send
+            + std::fmt::Debug
+            + 'static
+            + AsSproutKind,
+        <K as kube::Resource>::DynamicType: Default,
+    {
+        let _guard = self.locks.lock(infer_kind::<K>(), &request.spec.seed).await;
+        let Some(seed) = self.requested_seed::<K>(request).await? else {
+            return Ok(());
+        };
+        let namespace = request.namespace().unwrap_or_default();
+        self.throttle.acquire().await;
+        let ns_api: Api<Namespace> = Api::all(self.client.clone());
+        let targeted = match (
+            self.propagation_of(&seed).await,
+            ns_api.get_opt(&namespace).await?,
+        ) {
+            (Some(propagation), Some(ns)) => propagation.target.matches(&ns),
+            _ => false,
+        };
+        let mgr = self.resource_manager::<K>();
+        let requested = mgr
+            .list_requests(&request.spec.seed)
+            .await?
+            .iter()
+            .any(|r| {
+                r.spec.kind == request.spec.kind
+                    && r.namespace() == request.namespace()
+                    && r.name_any() != request.name_any()
+            });
+        if !targeted && !requested {
+            prune_sprout(&request.spec.seed, &seed.name_any(), &namespace, &mgr).await?;
+        }
+        Ok(())
+    }
+
+    /// Fetches the seed a SproutRequest refers to, if it exists and is a seed.
+    async fn requested_seed<K>(&self, request: &SproutRequest) -> Result<Option<K>>
+    where
+        K: kube::Resource<Scope = NamespaceResourceScope>
+            + Clone
+            + serde::de::DeserializeOwned
+            + serde::Serialize
+            + Sync
+            + Send
+            + std::fmt::Debug
+            + 'static
+            + AsSproutKind,
+        <K as kube::Resource>::DynamicType: Default,
+    {
+        let Some((namespace, name)) = request.spec.seed.split_once('/') else {
+            return Ok(None);
+        };
+        self.throttle.acquire().await;
+        let api: Api<K> = Api::namespaced(self.client.clone(), namespace);
+        match api.get_opt(name).await? {
+            // A seed that is being deleted grows no more sprouts
+            Some(seed) if seed.meta().deletion_timestamp.is_some() => Ok(None),
+            Some(seed) if self.is_seed(&seed).await => Ok(Some(seed)),
+            _ => Ok(None),
+        }
+    }
+
+    async fn init_policies(&self) -> Result<()> {
+        self.throttle.acquire().await;
+        let api: Api<SproutPolicy> = Api::all(self.client.clone());
+        let policies = match api.list(&ListParams::default()).await {
+            Ok(policies) => policies.items,
+            // The SproutPolicy CRD is not installed, so there are no policies
+            Err(kube::Error::Api(err)) if err.code == 404 => vec![],
+            Err(e) => return Err(e.into()),
+        };
+        let mut parsed = vec![];
+        for policy in &policies {
+            parsed.extend(self.parse_policy(policy).await);
+        }
+        info!("Loaded {} SproutPolicies", parsed.len());
+        let mut lock = self.policies.write().await;
+        *lock = parsed;
+        lock.sort_by_key(|p| p.policy.name_any());
+        Ok(())
+    }
+
+    /// The policy with its label selector parsed, or `None` (with a warning) if the selector is
+    /// invalid.
+    async fn parse_policy(&self, policy: &SproutPolicy) -> Option<ParsedPolicy> {
+        match ParsedPolicy::new(policy.clone()) {
+            Ok(parsed) => {
+                self.events
+                    .recovered::<SproutPolicy>(&policy.name_any(), None);
+                Some(parsed)
+            }
+            Err(e) => {
+                warn!(
+                    "SproutPolicy '{}' has an invalid label selector: {}",
+                    policy.name_any(),
+                    e
+                );
+                let event = SproutEvent::new(Reason::InvalidPropagation, None, e.to_string());
+                self.events.publish_on(policy, &event).await;
+                None
+            }
+        }
+    }
+
+    /// Registers a created or updated SproutPolicy, and grows or deletes the sprouts of the seeds
+    /// it selected before or selects now. A policy with an invalid label selector is rejected,
+    /// leaving its previous version in effect.
+    pub async fn apply_policy(&self, policy: &SproutPolicy) -> Result<()> {
+        let Some(parsed) = self.parse_policy(policy).await else {
+            return Ok(());
+        };
+        let previous = {
+            let mut lock = self.policies.write().await;
+            let previous = lock
+                .iter()
+                .find(|p| p.policy.name_any() == policy.name_any())
+                .cloned();
+            if previous
+                .as_ref()
+                .is_some_and(|p| p.policy.spec == policy.spec)
+            {
+                return Ok(());
+            }
+            lock.retain(|p| p.policy.name_any() != policy.name_any());
+            lock.push(parsed.clone());
+            lock.sort_by_key(|p| p.policy.name_any());
+            previous
+        };
+        info!("SproutPolicy '{}' applied", policy.name_any());
+        let changed: Vec<ParsedPolicy> = previous.clone().into_iter().chain([parsed]).collect();
+        let result = self.resync_policy_seeds(&changed).await;
+        if result.is_err() {
+            // Restore the previous policy, so a retry is not ignored as unchanged
+            let mut lock = self.policies.write().await;
+            lock.retain(|p| p.policy.name_any() != policy.name_any());
+            lock.extend(previous);
+            lock.sort_by_key(|p| p.policy.name_any());
+        }
+        result
+    }
+
+    /// Unregisters a deleted SproutPolicy, and grows or deletes the sprouts of the seeds it
+    /// selected.
+    pub async fn delete_policy(&self, policy: &SproutPolicy) -> Result<()> {
+        let removed: Vec<ParsedPolicy> = {
+            let mut lock = self.policies.write().await;
+            let removed = lock
+                .iter()
+                .filter(|p| p.policy.name_any() == policy.name_any())
+                .cloned()
+                .collect();
+            lock.retain(|p| p.policy.name_any() != policy.name_any());
+            removed
+        };
+        self.events
+            .recovered::<SproutPolicy>(&policy.name_any(), None);
+        info!("SproutPolicy '{}' deleted", policy.name_any());
+        self.resync_policy_seeds(&removed).await
+    }
+
+    async fn resync_policy_seeds(&self, policies: &[ParsedPolicy]) -> Result<()> {
+        if policies
+            .iter()
+            .any(|p| p.policy.spec.source.kind == SproutKind::ConfigMap)
+        {
+            self.resync_seeds::<ConfigMap>(policies).await?;
+        }
+        if policies
+            .iter()
+            .any(|p| p.policy.spec.source.kind == SproutKind::Secret)
+        {
+            self.resync_seeds::<Secret>(policies).await?;
+        }
+        Ok(())
+    }
+
+    /// Grows the sprouts of every object selected by one of the policies, or deletes them if the
+    /// object is no longer a seed.
+    async fn resync_seeds<K>(&self, policies: &[ParsedPolicy]) -> Result<()>
+    where
+        K: kube::Resource<Scope = NamespaceResourceScope>
+            + Clone
+            + serde::de::DeserializeOwned
+            + serde::Serialize
+            + Sync
+            + Send
+            + std::fmt::Debug
+            + 'static
+            + AsSproutKind,
+        <K as kube::Resource>::DynamicType: Default,
+    {
+        self.throttle.acquire().await;
+        let api: Api<K> = Api::all(self.client.clone());
+        let kind = infer_kind::<K>();
+        let selected = api
+            .list_metadata(&ListParams::default())
+            .await?
+            .into_iter()
+            .map(|meta| K::from_meta(meta.metadata))
+            .filter(|r| policies.iter().any(|p| p.selects(kind, r.meta())));
+        let mut failed = 0;
+        for resource in selected {
+            let seed = seed_ref(&resource);
+            let result = if self.is_seed(&resource).await {
+                match self.fetch_seed(&resource).await {
+                    Ok(Some(resource)) => self.add_seed(resource).await,
+                    Ok(None) => Ok(()),
+                    Err(e) => Err(e),
                 }
+            } else if self.is_known_seed(resource.clone()).await {
+                self.delete_seed(resource).await
+            } else {
+                Ok(())
+            };
+            if let Err(e) = result {
+                warn!("Resyncing '{}' failed: {}", seed, e);
+                failed += 1;
+            }
+        }
+        if failed > 0 {
+            bail!("{} {} seeds failed to resync", failed, kind);
+        }
+        Ok(())
+    }
+
+    /// Registers a created or updated ClusterSeed and grows its sprouts. If its kind changed, the
+    /// sprouts of the previous kind are deleted. Status updates of the ClusterSeed leave its spec
+    /// unchanged and are ignored.
+    pub async fn apply_cluster_seed(&self, seed: &ClusterSeed) -> Result<()> {
+        // Like a seed, a ClusterSeed that is being deleted is only kept around by the finalizer
+        if seed.metadata.deletion_timestamp.is_some() {
+            return self.delete_cluster_seed(seed).await;
+        }
+        let _guard = self.locks.lock("ClusterSeed", &seed.name_any()).await;
+        self.set_cluster_finalizer(seed, true).await?;
+        let previous = {
+            let mut lock = self.cluster_seeds.write().await;
+            let previous = lock.insert(seed.name_any(), seed.clone());
+            METRICS
+                .seeds
+                .with_label_values(&["ClusterSeed"])
+                .set(lock.len() as i64);
+            previous
+        };
+        if previous.as_ref().is_some_and(|p| p.spec == seed.spec) {
+            self.pending.forget("ClusterSeed", &seed.name_any());
+            return Ok(());
+        }
+        let result = self.regrow_cluster_seed(seed, previous.as_ref()).await;
+        let changed = previous.as_ref().is_none_or(|p| {
+            (&p.spec.kind, &p.spec.data, &p.spec.binary_data)
+                != (&seed.spec.kind, &seed.spec.data, &seed.spec.binary_data)
+        });
+        self.pending
+            .propagated("ClusterSeed", &seed.name_any(), changed, result.is_ok());
+        if result.is_err() {
+            // Forget the new spec, so a retry is not ignored as unchanged
+            let mut lock = self.cluster_seeds.write().await;
+            match previous {
+                Some(previous) => lock.insert(seed.name_any(), previous),
+                None => lock.remove(&seed.name_any()),
+            };
+        }
+        result
+    }
+
+    async fn regrow_cluster_seed(
+        &self,
+        seed: &ClusterSeed,
+        previous: Option<&ClusterSeed>,
+    ) -> Result<()> {
+        if let Some(previous) = previous.filter(|p| p.spec.kind != seed.spec.kind) {
+            self.delete_cluster_sprouts(previous).await?;
+        }
+        info!("Growing sprouts for ClusterSeed '{}'", seed.name_any());
+        let Some(propagation) = self.cluster_propagation_of(seed).await else {
+            return Ok(());
+        };
+        match seed.spec.kind {
+            SproutKind::ConfigMap => {
+                self.grow_cluster_sprouts::<ConfigMap>(seed, &propagation)
+                    .await
+            }
+            SproutKind::Secret => {
+                self.grow_cluster_sprouts::<Secret>(seed, &propagation)
+                    .await
+            }
+        }
+    }
+
+    async fn grow_cluster_sprouts<K>(
+        &self,
+        seed: &ClusterSeed,
+        propagation: &Propagation,
+    ) -> Result<()>
+    where
+        K: kube::Resource<Scope = NamespaceResourceScope>
+            + Clone
+            + serde::de::DeserializeOwned
+            + serde::Serialize
+            + Sync
+            + Send
+            + std::fmt::Debug
+            + 'static
+            + AsSproutKind,
+        <K as kube::Resource>::DynamicType: Default,
+    {
+        let resource = K::from_cluster_seed(seed);
+        self.sprout_hashes
+            .write()
+            .await
+            .insert((K::sprout_kind(), seed.name_any()), resource.hash());
+        let mgr = self.resource_manager::<K>();
+        let (report, failed) =
+            grow_sprouts(resource.clone(), &mgr, propagation, self.config.concurrency).await?;
+        self.save_report(&resource, &report).await;
+        if failed > 0 {
+            bail!(
+                "{} sprouts of ClusterSeed '{}' failed",
+                failed,
+                seed.name_any()
+            );
+        }
+        Ok(())
+    }
+
+    /// Unregisters a deleted ClusterSeed and deletes its sprouts.
+    pub async fn delete_cluster_seed(&self, seed: &ClusterSeed) -> Result<()> {
+        let _guard = self.locks.lock("ClusterSeed", &seed.name_any()).await;
+        {
+            let mut lock = self.cluster_seeds.write().await;
+            lock.remove(&seed.name_any());
+            METRICS
+                .seeds
+                .with_label_values(&["ClusterSeed"])
+                .set(lock.len() as i64);
+        }
+        self.pending.forget("ClusterSeed", &seed.name_any());
+        info!("Deleting sprouts for ClusterSeed '{}'", seed.name_any());
+        self.delete_cluster_sprouts(seed).await?;
+        self.set_cluster_finalizer(seed, false).await
+    }
+
+    /// Adds or removes the finalizer that keeps a ClusterSeed around until its sprouts are
+    /// deleted.
+    async fn set_cluster_finalizer(&self, seed: &ClusterSeed, present: bool) -> Result<()> {
+        let Some(finalizers) = toggle_finalizer(&seed.metadata, present) else {
+            return Ok(());
+        };
+        self.throttle.acquire().await;
+        let api: Api<ClusterSeed> = Api::all(self.client.clone());
+        // The resource version makes the patch fail if the finalizers changed in the meantime
+        let patch = json!({
+            "metadata": {
+                "finalizers": finalizers,
+                "resourceVersion": seed.resource_version(),
+            }
+        });
+        let params = PatchParams::default();
+        match api
+            .patch_metadata(&seed.name_any(), &params, &Patch::Merge(&patch))
+            .await
+        {
+            Ok(_) => Ok(()),
+            // A ClusterSeed that is gone needs no finalizer removed
+            Err(kube::Error::Api(err)) if err.code == 404 && !present => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn delete_cluster_sprouts(&self, seed: &ClusterSeed) -> Result<()> {
+        self.sprout_hashes
+            .write()
+            .await
+            .remove(&(seed.spec.kind, seed.name_any()));
+        self.reports
+            .write()
+            .await
+            .remove(&(seed.spec.kind, seed.name_any()));
+        match seed.spec.kind {
+            SproutKind::ConfigMap => {
+                let mgr = self.resource_manager::<ConfigMap>();
+                delete_sprouts(
+                    ConfigMap::from_cluster_seed(seed),
+                    &mgr,
+                    self.config.concurrency,
+                )
+                .await
+            }
+            SproutKind::Secret => {
+                let mgr = self.resource_manager::<Secret>();
+                delete_sprouts(
+                    Secret::from_cluster_seed(seed),
+                    &mgr,
+                    self.config.concurrency,
+                )
+                .await
+            }
+        }
+    }
+
+    /// The propagation of a ClusterSeed, or `None` (with a warning) if it is invalid.
+    async fn cluster_propagation_of(&self, seed: &ClusterSeed) -> Option<Propagation> {
+        match Propagation::from_cluster_seed(seed, &self.config) {
+            Ok(propagation) => {
+                self.events.recovered::<ClusterSeed>(&seed.name_any(), None);
+                Some(propagation)
+            }
+            Err(e) => {
+                warn!(
+                    "ClusterSeed '{}' has an invalid propagation: {}",
+                    seed.name_any(),
+                    e
+                );
+                let event = SproutEvent::new(Reason::InvalidPropagation, None, e.to_string());
+                self.events.publish_on(seed, &event).await;
+                None
+            }
+        }
+    }
+
+    /// The manager of sprouts of kind `K`, sharing the API request budget and the caches of all
+    /// seeds.
+    fn resource_manager<K>(&self) -> KubeResourceManager<K>
+    where
+        K: kube::Resource<Scope = NamespaceResourceScope>
+            + Clone
+            + serde::de::DeserializeOwned
+            + serde::Serialize
+            + std::fmt::Debug
+            + Cached,
+        <K as kube::Resource>::DynamicType: Default,
+    {
+        KubeResourceManager::new(
+            self.client.clone(),
+            self.throttle.clone(),
+            self.cache.clone(),
+            self.events.clone(),
+        )
+    }
+
+    /// Persists the report of a seed. Failing to do so is logged, as it does not affect the
+    /// sprouts themselves.
+    async fn save_report<K>(&self, seed: &K, report: &SproutReport)
+    where
+        K: kube::Resource<Scope = NamespaceResourceScope> + AsSproutKind,
+        <K as kube::Resource>::DynamicType: Default,
+    {
+        let result = match self.report_of(seed).await {
+            Ok(previous) => self.store_report(seed, previous, report.clone()).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            warn!("Saving the report of '{}' failed: {}", seed_ref(seed), e);
+        }
+    }
+
+    async fn update_report<K>(&self, seed: &K, namespace: &str, sprout: Option<SproutInventory>)
+    where
+        K: kube::Resource<Scope = NamespaceResourceScope> + AsSproutKind,
+        <K as kube::Resource>::DynamicType: Default,
+    {
+        let result = match self.report_of(seed).await {
+            Ok(previous) => {
+                let report = with_sprout(previous.clone().unwrap_or_default(), namespace, sprout);
+                self.store_report(seed, previous, report).await
             }
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            warn!("Updating the report of '{}' failed: {}", seed_ref(seed), e);
         }
+    }
 
-        info!("All known seeds sprouted in new namespace '{}'", namespace);
+    /// The report of a seed as last saved, looked up only if it is not known yet.
+    async fn report_of<K>(&self, seed: &K) -> Result<Option<SproutReport>>
+    where
+        K: kube::Resource<Scope = NamespaceResourceScope> + AsSproutKind,
+        <K as kube::Resource>::DynamicType: Default,
+    {
+        let key = (K::sprout_kind(), seed_ref(seed));
+        if let Some(report) = self.reports.read().await.get(&key) {
+            return Ok(Some(report.clone()));
+        }
+        load_report(&self.client, &self.throttle, seed).await
+    }
+
+    /// Saves the report of a seed, unless only the sync times changed since the previous one.
+    async fn store_report<K>(
+        &self,
+        seed: &K,
+        previous: Option<SproutReport>,
+        report: SproutReport,
+    ) -> Result<()>
+    where
+        K: kube::Resource<Scope = NamespaceResourceScope> + AsSproutKind,
+        <K as kube::Resource>::DynamicType: Default,
+    {
+        let key = (K::sprout_kind(), seed_ref(seed));
+        // Sprouts that are fine again are warned about anew if they conflict or drift again
+        for sprout in report.sprouts.iter().filter(|s| s.error.is_none()) {
+            self.events
+                .recovered::<K>(&seed_ref(seed), Some(&sprout.namespace));
+        }
+        let Some(report) = changed_report(previous.as_ref(), report) else {
+            if let Some(previous) = previous {
+                self.reports.write().await.insert(key, previous);
+            }
+            return Ok(());
+        };
+        save_report(&self.client, &self.throttle, seed, &report).await?;
+        self.reports.write().await.insert(key, report);
         Ok(())
     }
 }
+
+/// Whether every sprout in the report is ready, i.e. grown from the seed data it was made for.
+fn is_synced(report: &SproutReport) -> bool {
+    report.sprouts.iter().all(|s| s.ready)
+}
+
+/// Updates the gauge of known seeds by kind.
+fn count_seeds(seeds: &HashSet<Seed>) {
+    for kind in [SproutKind::ConfigMap, SproutKind::Secret] {
+        let count = seeds.iter().filter(|s| s.resource_type == kind).count();
+        METRICS
+            .seeds
+            .with_label_values(&[&kind.to_string()])
+            .set(count as i64);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{ClusterSeedSpec, DriftPolicy, PolicySource, SproutPolicySpec};
+    use crate::utils::{ANNOTATION_KEY, FINALIZER, create_sprout};
+    use http::{Method, Request, Response};
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+    use kube::Resource;
+    use kube::client::Body;
+    use serde_json::Value;
+
+    /// A client of an API server that answers the lists of namespaces, ConfigMaps and
+    /// SproutRequests, and records every request made.
+    fn client(requests: Arc<Mutex<Vec<(Method, String)>>>) -> Client {
+        let service = tower::service_fn(move |req: Request<Body>| {
+            let path = req.uri().path().to_string();
+            requests
+                .lock()
+                .unwrap()
+                .push((req.method().clone(), path.clone()));
+            async move {
+                let (code, body) = route(&path);
+                let response = Response::builder()
+                    .status(code)
+                    .body(Body::from(serde_json::to_vec(&body).unwrap()))
+                    .unwrap();
+                Ok::<_, std::convert::Infallible>(response)
+            }
+        });
+        Client::new(service, "default")
+    }
+
+    fn route(path: &str) -> (u16, Value) {
+        let list = |kind: &str, items: Vec<Value>| json!({ "apiVersion": "v1", "kind": kind, "metadata": {}, "items": items });
+        let namespace = |name: &str| json!({ "metadata": { "name": name } });
+        match path {
+            "/api/v1/namespaces" => (
+                200,
+                list(
+                    "NamespaceList",
+                    vec![namespace("source"), namespace("ns-a"), namespace("ns-b")],
+                ),
+            ),
+            "/api/v1/namespaces/source/configmaps/dying" => {
+                let mut dying = seed();
+                dying.metadata.name = Some("dying".to_string());
+                dying.metadata.deletion_timestamp = Some(Time(Utc::now()));
+                (200, serde_json::to_value(dying).unwrap())
+            }
+            "/api/v1/configmaps" => (200, list("ConfigMapList", vec![])),
+            "/apis/sprouter.geeko.me/v1alpha1/sproutrequests" => {
+                (200, list("SproutRequestList", vec![]))
+            }
+            _ => (404, json!({ "kind": "Status", "code": 404 })),
+        }
+    }
+
+    fn seed() -> ConfigMap {
+        let mut cm = ConfigMap::default();
+        cm.metadata.name = Some("shared".to_string());
+        cm.metadata.namespace = Some("source".to_string());
+        cm.metadata.resource_version = Some("1".to_string());
+        cm.metadata.finalizers = Some(vec![FINALIZER.to_string()]);
+        cm.metadata.annotations = Some(BTreeMap::from([(
+            ANNOTATION_KEY.to_string(),
+            "true".to_string(),
+        )]));
+        cm.data = Some(BTreeMap::from([("key".to_string(), "value".to_string())]));
+        cm
+    }
+
+    fn report(seed: &ConfigMap, namespaces: &[&str]) -> SproutReport {
+        let propagation =
+            Propagation::from_annotations(seed.meta(), SproutKind::ConfigMap, &Config::default());
+        let (_, hash) = propagation.unwrap().sprout(seed.clone());
+        let sprouts = namespaces
+            .iter()
+            .map(|ns| SproutInventory {
+                namespace: ns.to_string(),
+                ready: true,
+                observed_hash: hash.clone(),
+                last_sync: Time(Utc::now()),
+                error: None,
+            })
+            .collect();
+        SproutReport::new(hash, sprouts)
+    }
+
+    #[tokio::test]
+    async fn test_resumed_seed_in_sync_makes_no_writes() {
+        let requests = Arc::new(Mutex::new(vec![]));
+        let (cache, _) = Cache::new();
+        let manager = SproutManager::new(client(requests.clone()), Config::default(), cache);
+        let seed = seed();
+        // As loaded by `warm` at startup
+        let key = (SproutKind::ConfigMap, "source/shared".to_string());
+        manager.resumable.write().await.insert(key.clone());
+        let report = report(&seed, &["ns-a", "ns-b"]);
+        manager.reports.write().await.insert(key, report);
+
+        manager.resume_seed(seed.clone()).await.unwrap();
+        assert!(manager.is_known_seed(seed).await);
+        let requests = requests.lock().unwrap();
+        assert!(!requests.is_empty());
+        assert!(requests.iter().all(|(method, _)| method == Method::GET));
+    }
+
+    #[tokio::test]
+    async fn test_seed_being_deleted_is_not_resolved() {
+        let requests = Arc::new(Mutex::new(vec![]));
+        let (cache, _) = Cache::new();
+        let manager = SproutManager::new(client(requests.clone()), Config::default(), cache);
+
+        let resolved = manager.resolve_seed::<ConfigMap>("source/dying").await;
+        assert!(resolved.unwrap().is_none());
+        let mut sprout = create_sprout(seed(), &None);
+        sprout.metadata.name = Some("dying".to_string());
+        sprout.metadata.namespace = Some("ns-a".to_string());
+        manager.sprout_deleted(sprout).await.unwrap();
+        let requests = requests.lock().unwrap();
+        assert!(requests.iter().all(|(method, _)| method == Method::GET));
+    }
+
+    #[tokio::test]
+    async fn test_deleted_cluster_seed_loses_its_finalizer() {
+        let requests = Arc::new(Mutex::new(vec![]));
+        let (cache, _) = Cache::new();
+        let manager = SproutManager::new(client(requests.clone()), Config::default(), cache);
+        let mut seed = ClusterSeed::new(
+            "shared",
+            ClusterSeedSpec {
+                kind: SproutKind::ConfigMap,
+                data: BTreeMap::from([("key".to_string(), "value".to_string())]),
+                binary_data: BTreeMap::new(),
+                targets: Default::default(),
+                drift_policy: DriftPolicy::Revert,
+            },
+        );
+        seed.metadata.finalizers = Some(vec![FINALIZER.to_string()]);
+        seed.metadata.deletion_timestamp = Some(Time(Utc::now()));
+
+        manager.apply_cluster_seed(&seed).await.unwrap();
+        let requests = requests.lock().unwrap();
+        let patch = (
+            Method::PATCH,
+            "/apis/sprouter.geeko.me/v1alpha1/clusterseeds/shared".to_string(),
+        );
+        assert!(requests.contains(&patch));
+    }
+
+    #[tokio::test]
+    async fn test_policy_with_invalid_selector_is_rejected() {
+        let requests = Arc::new(Mutex::new(vec![]));
+        let (cache, _) = Cache::new();
+        let manager = SproutManager::new(client(requests.clone()), Config::default(), cache);
+        let policy = SproutPolicy::new(
+            "shared",
+            SproutPolicySpec {
+                source: PolicySource {
+                    kind: SproutKind::ConfigMap,
+                    namespace: None,
+                    name: None,
+                    label_selector: Some("app in (shared".to_string()),
+                },
+                targets: Default::default(),
+                requestable: false,
+                update_strategy: Default::default(),
+                drift_policy: DriftPolicy::Revert,
+                transform: Default::default(),
+            },
+        );
+
+        manager.apply_policy(&policy).await.unwrap();
+        assert!(manager.policies.read().await.is_empty());
+        let requests = requests.lock().unwrap();
+        assert!(
+            requests
+                .iter()
+                .all(|(method, path)| method == Method::POST && path.ends_with("/events"))
+        );
+        assert!(!requests.is_empty());
+    }
+}
@@ -1,4 +1,9 @@
 // Copyright 2025, Jeroen van Erp <jeroen@geeko.me>
 // SPDX-License-Identifier: Apache-2.0
 pub mod kind;
+pub mod latency;
+pub mod locks;
 pub mod manager;
+pub mod policy;
+pub mod report;
+pub mod target;
@@ -0,0 +1,296 @@
+// Copyright 2025, Jeroen van Erp <jeroen@geeko.me>
+// SPDX-License-Identifier: Apache-2.0
+use std::collections::BTreeMap;
+
+use anyhow::{Result, bail};
+use kube::ResourceExt;
+use kube::api::ObjectMeta;
+use kube::core::{Selector, SelectorExt};
+
+use crate::config::Config;
+use crate::sprout::kind::{AsSproutKind, SproutKind};
+use crate::sprout::target::{Target, parse_selector};
+use crate::types::{ClusterSeed, DriftPolicy, SproutPolicy, Transform, UpdateStrategy};
+use crate::utils::{
+    DRIFT_POLICY_KEY, create_sprout, hash_seed_data, is_requestable, is_sprout, seed_ref_of,
+};
+
+/// How a seed propagates: where its sprouts grow, whether they can be requested, when existing
+/// sprouts are updated, what happens to sprouts that drifted and how sprouts differ from the seed.
+#[derive(Debug, Clone, Default)]
+pub struct Propagation {
+    pub target: Target,
+    pub requestable: bool,
+    pub strategy: UpdateStrategy,
+    pub drift: DriftPolicy,
+    pub transform: Transform,
+}
+
+impl Propagation {
+    /// The propagation declared through annotations on the seed itself.
+    pub fn from_annotations(meta: &ObjectMeta, kind: SproutKind, config: &Config) -> Result<Self> {
+        Ok(Self {
+            target: Target::from_meta(meta, kind, config)?,
+            requestable: is_requestable(meta),
+            drift: drift_policy(meta)?,
+            ..Default::default()
+        })
+    }
+
+    /// The propagation of a seed selected by a SproutPolicy.
+    pub fn from_policy(
+        meta: &ObjectMeta,
+        kind: SproutKind,
+        policy: &SproutPolicy,
+        config: &Config,
+    ) -> Result<Self> {
+        Ok(Self {
+            target: Target::from_spec(seed_ref_of(meta), kind, &policy.spec.targets, config)?,
+            requestable: policy.spec.requestable,
+            strategy: policy.spec.update_strategy,
+            drift: policy.spec.drift_policy,
+            transform: policy.spec.transform.clone(),
+        })
+    }
+
+    /// The propagation of a ClusterSeed.
+    pub fn from_cluster_seed(seed: &ClusterSeed, config: &Config) -> Result<Self> {
+        Ok(Self {
+            target: Target::from_spec(seed.name_any(), seed.spec.kind, &seed.spec.targets, config)?,
+            drift: seed.spec.drift_policy,
+            ..Default::default()
+        })
+    }
+
+    /// Creates the sprout of a seed, together with the hash recorded on it.
+    pub fn sprout<K>(&self, seed: K) -> (K, Option<String>)
+    where
+        K: kube::Resource<Scope = kube::core::NamespaceResourceScope> + Clone + AsSproutKind,
+    {
+        let hash = self.hash(&seed);
+        let mut res = seed;
+        res.labels_mut().extend(self.transform.labels.clone());
+        res.annotations_mut()
+            .extend(self.transform.annotations.clone());
+        (create_sprout(res, &hash), hash)
+    }
+
+    /// The hash of the seed data, which also covers the transformation so that changing it
+    /// updates the sprouts.
+    fn hash<K: AsSproutKind>(&self, seed: &K) -> Option<String> {
+        let hash = seed.hash();
+        if self.transform.is_empty() {
+            return hash;
+        }
+        let transform = serde_json::to_string(&self.transform).unwrap_or_default();
+        Some(hash_seed_data(&BTreeMap::from([
+            ("data".to_string(), hash.unwrap_or_default()),
+            ("transform".to_string(), transform),
+        ])))
+    }
+}
+
+/// The drift policy annotated on a seed, `Revert` if there is none.
+fn drift_policy(meta: &ObjectMeta) -> Result<DriftPolicy> {
+    match meta
+        .annotations
+        .as_ref()
+        .and_then(|a| a.get(DRIFT_POLICY_KEY))
+        .map(String::as_str)
+    {
+        None | Some("Revert") => Ok(DriftPolicy::Revert),
+        Some("Flag") => Ok(DriftPolicy::Flag),
+        Some(other) => bail!(
+            "Invalid {}: '{}', expected 'Revert' or 'Flag'",
+            DRIFT_POLICY_KEY,
+            other
+        ),
+    }
+}
+
+/// A SproutPolicy together with its parsed label selector, so the selector is validated once
+/// when the policy is applied rather than on every seed it is matched against.
+#[derive(Debug, Clone)]
+pub struct ParsedPolicy {
+    pub policy: SproutPolicy,
+    selector: Option<Selector>,
+}
+
+impl ParsedPolicy {
+    /// Parses the label selector of the policy, failing if it is invalid.
+    pub fn new(policy: SproutPolicy) -> Result<Self> {
+        let selector = match policy.spec.source.label_selector.as_deref() {
+            Some(s) => Some(parse_selector(s)?),
+            None => None,
+        };
+        Ok(Self { policy, selector })
+    }
+
+    /// Whether the policy selects the object of the given kind as a seed. Sprouts are never
+    /// selected, as they carry the labels of their seed.
+    pub fn selects(&self, kind: SproutKind, meta: &ObjectMeta) -> bool {
+        let source = &self.policy.spec.source;
+        source.kind == kind
+            && !is_sprout(meta)
+            && source
+                .namespace
+                .as_ref()
+                .is_none_or(|ns| meta.namespace.as_ref() == Some(ns))
+            && source
+                .name
+                .as_ref()
+                .is_none_or(|name| meta.name.as_ref() == Some(name))
+            && self
+                .selector
+                .as_ref()
+                .is_none_or(|sel| sel.matches(meta.labels.as_ref().unwrap_or(&BTreeMap::new())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{PolicySource, SproutPolicySpec};
+    use crate::utils::NAMESPACE_SELECTOR_KEY;
+    use k8s_openapi::api::core::v1::ConfigMap;
+
+    fn sprout_policy(source: PolicySource) -> SproutPolicy {
+        SproutPolicy::new(
+            "shared",
+            SproutPolicySpec {
+                source,
+                targets: Default::default(),
+                requestable: false,
+                update_strategy: UpdateStrategy::Always,
+                drift_policy: DriftPolicy::Revert,
+                transform: Default::default(),
+            },
+        )
+    }
+
+    fn policy(source: PolicySource) -> ParsedPolicy {
+        ParsedPolicy::new(sprout_policy(source)).unwrap()
+    }
+
+    fn meta(ns: &str, name: &str, labels: &[(&str, &str)]) -> ObjectMeta {
+        ObjectMeta {
+            namespace: Some(ns.to_string()),
+            name: Some(name.to_string()),
+            labels: Some(
+                labels
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect(),
+            ),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_selects_by_namespace_name_and_kind() {
+        let p = policy(PolicySource {
+            kind: SproutKind::ConfigMap,
+            namespace: Some("shared".to_string()),
+            name: Some("config".to_string()),
+            label_selector: None,
+        });
+        let cm = SproutKind::ConfigMap;
+        let seed = meta("shared", "config", &[]);
+        assert!(p.selects(cm, &seed));
+        assert!(!p.selects(SproutKind::Secret, &seed));
+        assert!(!p.selects(cm, &meta("other", "config", &[])));
+        assert!(!p.selects(cm, &meta("shared", "other", &[])));
+    }
+
+    #[test]
+    fn test_selects_by_labels_but_never_sprouts() {
+        let p = policy(PolicySource {
+            kind: SproutKind::ConfigMap,
+            namespace: None,
+            name: None,
+            label_selector: Some("app=shared".to_string()),
+        });
+        let kind = SproutKind::ConfigMap;
+        let seed = meta("shared", "config", &[("app", "shared")]);
+        assert!(p.selects(kind, &seed));
+        assert!(!p.selects(kind, &meta("shared", "config", &[])));
+
+        let sprout = create_sprout(
+            ConfigMap {
+                metadata: seed,
+                ..Default::default()
+            },
+            &None,
+        );
+        assert!(!p.selects(kind, &sprout.metadata));
+    }
+
+    #[test]
+    fn test_invalid_label_selector_is_rejected() {
+        let policy = sprout_policy(PolicySource {
+            kind: SproutKind::ConfigMap,
+            namespace: None,
+            name: None,
+            label_selector: Some("app in (shared".to_string()),
+        });
+        assert!(ParsedPolicy::new(policy).is_err());
+    }
+
+    #[test]
+    fn test_from_annotations_invalid() {
+        let mut seed = meta("shared", "config", &[]);
+        seed.annotations = Some(BTreeMap::from([(
+            NAMESPACE_SELECTOR_KEY.to_string(),
+            "team in (payments".to_string(),
+        )]));
+        assert!(
+            Propagation::from_annotations(&seed, SproutKind::ConfigMap, &Config::default())
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_from_annotations_drift_policy() {
+        let mut seed = meta("shared", "config", &[]);
+        let drift = |seed: &ObjectMeta| {
+            Propagation::from_annotations(seed, SproutKind::ConfigMap, &Config::default())
+                .map(|p| p.drift)
+        };
+        assert_eq!(drift(&seed).unwrap(), DriftPolicy::Revert);
+        seed.annotations = Some(BTreeMap::from([(
+            DRIFT_POLICY_KEY.to_string(),
+            "Flag".to_string(),
+        )]));
+        assert_eq!(drift(&seed).unwrap(), DriftPolicy::Flag);
+        seed.annotations = Some(BTreeMap::from([(
+            DRIFT_POLICY_KEY.to_string(),
+            "Ignore".to_string(),
+        )]));
+        assert!(drift(&seed).is_err());
+    }
+
+    #[test]
+    fn test_sprout_applies_transform() {
+        let cm = ConfigMap {
+            metadata: meta("shared", "config", &[]),
+            data: Some(BTreeMap::from([("key".to_string(), "value".to_string())])),
+            ..Default::default()
+        };
+
+        let plain = Propagation::default();
+        let (_, plain_hash) = plain.sprout(cm.clone());
+        assert_eq!(plain_hash, cm.hash());
+
+        let transformed = Propagation {
+            transform: Transform {
+                labels: BTreeMap::from([("team".to_string(), "payments".to_string())]),
+                annotations: Default::default(),
+            },
+            ..Default::default()
+        };
+        let (sprout, hash) = transformed.sprout(cm.clone());
+        let team = sprout.labels().get("team");
+        assert_eq!(team.map(String::as_str), Some("payments"));
+        assert_ne!(hash, plain_hash);
+    }
+}
@@ -0,0 +1,196 @@
+// Copyright 2025, Jeroen van Erp <jeroen@geeko.me>
+// SPDX-License-Identifier: Apache-2.0
+use anyhow::Result;
+use k8s_openapi::NamespaceResourceScope;
+use kube::api::{Patch, PatchParams};
+use kube::{Api, Client, Resource, ResourceExt};
+use serde_json::json;
+
+use crate::kubernetes::throttle::Throttle;
+use crate::sprout::kind::AsSproutKind;
+use crate::types::{ClusterSeed, SproutInventory, SproutReport, SproutStatus, SproutStatusSpec};
+use crate::utils::seed_ref;
+
+const FIELD_MANAGER: &str = "sprouter";
+
+/// The name of the SproutStatus of a seed, which is unique per kind within the namespace.
+pub fn status_name<K: AsSproutKind>(name: &str) -> String {
+    format!("{}-{}", K::sprout_kind().to_string().to_lowercase(), name)
+}
+
+/// Persists the report of a seed: in a SproutStatus next to a ConfigMap or Secret seed, owned by
+/// the seed so it goes when the seed does, or in the status of a ClusterSeed.
+pub async fn save_report<K>(
+    client: &Client,
+    throttle: &Throttle,
+    seed: &K,
+    report: &SproutReport,
+) -> Result<()>
+where
+    K: Resource<Scope = NamespaceResourceScope> + AsSproutKind,
+    <K as Resource>::DynamicType: Default,
+{
+    let params = PatchParams::apply(FIELD_MANAGER).force();
+    let Some(namespace) = seed.namespace() else {
+        let api: Api<ClusterSeed> = Api::all(client.clone());
+        throttle.acquire().await;
+        api.patch_status(
+            &seed.name_any(),
+            &params,
+            &status_patch::<ClusterSeed>(report),
+        )
+        .await?;
+        return Ok(());
+    };
+
+    let api: Api<SproutStatus> = Api::namespaced(client.clone(), &namespace);
+    let name = status_name::<K>(&seed.name_any());
+    let mut status = SproutStatus::new(
+        &name,
+        SproutStatusSpec {
+            seed: seed_ref(seed),
+            kind: K::sprout_kind(),
+        },
+    );
+    status.metadata.owner_references = seed.owner_ref(&Default::default()).map(|owner| vec![owner]);
+    throttle.acquire().await;
+    api.patch(&name, &params, &Patch::Apply(&status)).await?;
+    throttle.acquire().await;
+    api.patch_status(&name, &params, &status_patch::<SproutStatus>(report))
+        .await?;
+    Ok(())
+}
+
+/// The report with the entry of a single namespace replaced, or removed if `sprout` is `None`.
+pub fn with_sprout(
+    report: SproutReport,
+    namespace: &str,
+    sprout: Option<SproutInventory>,
+) -> SproutReport {
+    let sprouts = report
+        .sprouts
+        .into_iter()
+        .filter(|s| s.namespace != namespace)
+        .chain(sprout)
+        .collect();
+    SproutReport::new(report.seed_hash, sprouts)
+}
+
+/// The report to save in place of the previous one, in which sprouts whose state did not change
+/// keep the time they were last synced at, or `None` if nothing changed at all.
+pub fn changed_report(
+    previous: Option<&SproutReport>,
+    mut report: SproutReport,
+) -> Option<SproutReport> {
+    let Some(previous) = previous else {
+        return Some(report);
+    };
+    for sprout in &mut report.sprouts {
+        let unchanged = previous.sprouts.iter().find(|p| {
+            p.namespace == sprout.namespace
+                && p.ready == sprout.ready
+                && p.observed_hash == sprout.observed_hash
+                && p.error == sprout.error
+        });
+        if let Some(unchanged) = unchanged {
+            sprout.last_sync = unchanged.last_sync.clone();
+        }
+    }
+    (report != *previous).then_some(report)
+}
+
+/// Deletes the SproutStatus of a seed that is no longer a seed.
+pub async fn delete_report<K>(client: &Client, throttle: &Throttle, seed: &K) -> Result<()>
+where
+    K: Resource<Scope = NamespaceResourceScope> + AsSproutKind,
+    <K as Resource>::DynamicType: Default,
+{
+    let Some(namespace) = seed.namespace() else {
+        return Ok(());
+    };
+    let api: Api<SproutStatus> = Api::namespaced(client.clone(), &namespace);
+    throttle.acquire().await;
+    match api
+        .delete(&status_name::<K>(&seed.name_any()), &Default::default())
+        .await
+    {
+        Ok(_) => Ok(()),
+        Err(kube::Error::Api(err)) if err.code == 404 => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// The report of a seed as it was last saved, if any.
+pub async fn load_report<K>(
+    client: &Client,
+    throttle: &Throttle,
+    seed: &K,
+) -> Result<Option<SproutReport>>
+where
+    K: Resource<Scope = NamespaceResourceScope> + AsSproutKind,
+    <K as Resource>::DynamicType: Default,
+{
+    throttle.acquire().await;
+    let Some(namespace) = seed.namespace() else {
+        let api: Api<ClusterSeed> = Api::all(client.clone());
+        return Ok(api.get_opt(&seed.name_any()).await?.and_then(|s| s.status));
+    };
+    let api: Api<SproutStatus> = Api::namespaced(client.clone(), &namespace);
+    Ok(api
+        .get_opt(&status_name::<K>(&seed.name_any()))
+        .await?
+        .and_then(|s| s.status))
+}
+
+fn status_patch<R: Resource<DynamicType = ()>>(report: &SproutReport) -> Patch<serde_json::Value> {
+    Patch::Apply(json!({
+        "apiVersion": R::api_version(&()),
+        "kind": R::kind(&()),
+        "status": report,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+    use k8s_openapi::chrono::{DateTime, Utc};
+
+    fn sprout(namespace: &str, ready: bool, synced: DateTime<Utc>) -> SproutInventory {
+        SproutInventory {
+            namespace: namespace.to_string(),
+            ready,
+            observed_hash: None,
+            last_sync: Time(synced),
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_changed_report_ignores_sync_times() {
+        let before = DateTime::<Utc>::from_timestamp(0, 0).unwrap();
+        let now = Utc::now();
+        let previous = SproutReport::new(None, vec![sprout("ns-a", true, before)]);
+
+        let resynced = SproutReport::new(None, vec![sprout("ns-a", true, now)]);
+        assert_eq!(changed_report(Some(&previous), resynced), None);
+
+        let changed = SproutReport::new(
+            None,
+            vec![sprout("ns-a", true, now), sprout("ns-b", false, now)],
+        );
+        let report = changed_report(Some(&previous), changed).unwrap();
+        assert_eq!(report.sprouts[0].last_sync, Time(before));
+        assert_eq!(report.sprouts[1].last_sync, Time(now));
+    }
+
+    #[test]
+    fn test_with_sprout_replaces_the_namespace() {
+        let now = Utc::now();
+        let report = SproutReport::new(None, vec![sprout("ns-a", false, now)]);
+        let report = with_sprout(report, "ns-a", Some(sprout("ns-a", true, now)));
+        assert_eq!(report.ready, "1/1");
+        let report = with_sprout(report, "ns-a", None);
+        assert_eq!(report.ready, "0/0");
+    }
+}
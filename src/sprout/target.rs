@@ -0,0 +1,480 @@
+// Copyright 2025, Jeroen van Erp <jeroen@geeko.me>
+// SPDX-License-Identifier: Apache-2.0
+use std::collections::BTreeSet;
+
+use anyhow::{Result, bail};
+use k8s_openapi::api::core::v1::Namespace;
+use kube::ResourceExt;
+use kube::api::ObjectMeta;
+use kube::core::{Expression, Selector, SelectorExt};
+use regex::Regex;
+
+use crate::config::Config;
+use crate::sprout::kind::SproutKind;
+use crate::types::TargetSpec;
+use crate::utils::{
+    EXCLUDE_KEY, IGNORE_KEY, INCLUDE_KEY, NAMESPACE_SELECTOR_KEY, RECEIVE_KEY, TARGETS_KEY,
+    seed_ref_of,
+};
+
+/// The set of namespaces a seed should sprout into, as declared on the seed (or by a policy) and
+/// limited by the operator configuration.
+#[derive(Debug, Clone, Default)]
+pub struct Target {
+    seed: String,
+    kind: Option<SproutKind>,
+    targets: Option<BTreeSet<String>>,
+    selector: Option<Selector>,
+    include: Vec<Pattern>,
+    exclude: Vec<Pattern>,
+    excluded: Vec<Pattern>,
+    opt_in: bool,
+}
+
+impl Target {
+    /// The target declared through annotations on the seed.
+    pub fn from_meta(meta: &ObjectMeta, kind: SproutKind, config: &Config) -> Result<Self> {
+        let annotation = |key: &str| meta.annotations.as_ref().and_then(|a| a.get(key));
+        let spec = TargetSpec {
+            namespaces: annotation(TARGETS_KEY).map(|s| split_list(s)),
+            namespace_selector: annotation(NAMESPACE_SELECTOR_KEY).cloned(),
+            include: annotation(INCLUDE_KEY)
+                .map(|s| split_patterns(s))
+                .transpose()?
+                .unwrap_or_default(),
+            exclude: annotation(EXCLUDE_KEY)
+                .map(|s| split_patterns(s))
+                .transpose()?
+                .unwrap_or_default(),
+        };
+        Self::from_spec(seed_ref_of(meta), kind, &spec, config)
+    }
+
+    pub fn from_spec(
+        seed: String,
+        kind: SproutKind,
+        spec: &TargetSpec,
+        config: &Config,
+    ) -> Result<Self> {
+        let patterns = |p: &[String]| p.iter().map(|p| Pattern::parse(p)).collect::<Result<_>>();
+        Ok(Self {
+            seed,
+            kind: Some(kind),
+            targets: spec
+                .namespaces
+                .as_ref()
+                .map(|t| t.iter().cloned().collect()),
+            selector: spec
+                .namespace_selector
+                .as_deref()
+                .map(parse_selector)
+                .transpose()?,
+            include: patterns(&spec.include)?,
+            exclude: patterns(&spec.exclude)?,
+            excluded: config.excluded_namespaces.clone(),
+            opt_in: config.opt_in,
+        })
+    }
+
+    pub fn matches(&self, ns: &Namespace) -> bool {
+        if !self.accepted_by(ns) {
+            return false;
+        }
+        let name = ns.name_any();
+        if self.targets.as_ref().is_some_and(|t| !t.contains(&name)) {
+            return false;
+        }
+        if self.excluded.iter().any(|p| p.matches(&name))
+            || self.exclude.iter().any(|p| p.matches(&name))
+        {
+            return false;
+        }
+        if !self.include.is_empty() && !self.include.iter().any(|p| p.matches(&name)) {
+            return false;
+        }
+        self.selector
+            .as_ref()
+            .map(|s| s.matches(ns.labels()))
+            .unwrap_or(true)
+    }
+
+    /// Whether the namespace itself is willing to receive sprouts of this seed.
+    fn accepted_by(&self, ns: &Namespace) -> bool {
+        if self.opt_in && ns.labels().get(RECEIVE_KEY).map(String::as_str) != Some("true") {
+            return false;
+        }
+        match ns.annotations().get(IGNORE_KEY) {
+            Some(ignore) if ignore == "true" => false,
+            Some(ignore) => !ignore.split(',').any(|s| self.ignored_by(s.trim())),
+            None => true,
+        }
+    }
+
+    /// Whether an entry of a namespace's ignore list refers to this seed. An entry is either
+    /// `Kind/namespace/name`, or `namespace/name` for seeds of every kind; ClusterSeeds have no
+    /// namespace. Kinds start with a capital, which a namespace never does.
+    fn ignored_by(&self, entry: &str) -> bool {
+        match entry.split_once('/') {
+            Some((kind, seed)) if kind.starts_with(char::is_uppercase) => {
+                self.kind.is_some_and(|k| k.to_string() == kind) && seed == self.seed
+            }
+            _ => entry == self.seed,
+        }
+    }
+}
+
+/// A namespace name pattern: either a glob supporting `*` and `?`, or a regular expression
+/// enclosed in slashes, e.g. `/^team-(a|b)$/`.
+#[derive(Debug, Clone)]
+pub struct Pattern(Regex);
+
+impl Pattern {
+    pub fn parse(pattern: &str) -> Result<Self> {
+        let regex = match pattern.strip_prefix('/').and_then(|p| p.strip_suffix('/')) {
+            Some(re) => Regex::new(re)?,
+            None => {
+                let glob = regex::escape(pattern)
+                    .replace(r"\*", ".*")
+                    .replace(r"\?", ".");
+                Regex::new(&format!("^{}$", glob))?
+            }
+        };
+        Ok(Self(regex))
+    }
+
+    pub fn matches(&self, name: &str) -> bool {
+        self.0.is_match(name)
+    }
+}
+
+/// Parses a comma-separated list of namespace name patterns.
+pub fn parse_patterns(input: &str) -> Result<Vec<Pattern>> {
+    split_patterns(input)?
+        .iter()
+        .map(|p| Pattern::parse(p))
+        .collect()
+}
+
+/// Splits a comma-separated list of namespace name patterns. A regular expression ends at its
+/// closing slash rather than at a comma, so it may contain commas, e.g. `/^[a-z]{1,3}$/`, but no
+/// slashes, which namespace names cannot contain anyway.
+fn split_patterns(input: &str) -> Result<Vec<String>> {
+    let mut patterns = Vec::new();
+    let mut rest = input.trim_start();
+    while !rest.is_empty() {
+        let end = match rest.strip_prefix('/') {
+            Some(regex) => match regex.find('/') {
+                Some(i) => i + 2,
+                None => bail!("Unterminated regular expression in '{}'", input),
+            },
+            None => rest.find(',').unwrap_or(rest.len()),
+        };
+        let (pattern, tail) = rest.split_at(end);
+        let pattern = pattern.trim();
+        if !pattern.is_empty() {
+            patterns.push(pattern.to_string());
+        }
+        let tail = tail.trim_start();
+        rest = match tail.strip_prefix(',') {
+            Some(tail) => tail.trim_start(),
+            None if tail.is_empty() => tail,
+            None => bail!("Expected ',' after '{}' in '{}'", pattern, input),
+        };
+    }
+    Ok(patterns)
+}
+
+fn split_list(input: &str) -> Vec<String> {
+    input
+        .split(',')
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Parses a label selector in the string format used by `kubectl -l`, e.g.
+/// `team=payments,env in (prod,staging),!legacy`.
+pub fn parse_selector(input: &str) -> Result<Selector> {
+    let mut expressions = Vec::new();
+    for requirement in split_requirements(input)? {
+        expressions.push(parse_requirement(requirement)?);
+    }
+    Ok(Selector::from_iter(expressions))
+}
+
+fn split_requirements(input: &str) -> Result<Vec<&str>> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in input.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 0 => bail!("Unbalanced ')' in selector '{}'", input),
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(&input[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    if depth != 0 {
+        bail!("Unbalanced '(' in selector '{}'", input);
+    }
+    parts.push(&input[start..]);
+    Ok(parts
+        .into_iter()
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .collect())
+}
+
+fn parse_requirement(req: &str) -> Result<Expression> {
+    if let Some(key) = req.strip_prefix('!') {
+        return Ok(Expression::DoesNotExist(parse_key(key, req)?));
+    }
+    if let Some((key, value)) = req.split_once("!=") {
+        return Ok(Expression::NotEqual(
+            parse_key(key, req)?,
+            value.trim().to_string(),
+        ));
+    }
+    if let Some((key, value)) = req.split_once("==").or_else(|| req.split_once('=')) {
+        return Ok(Expression::Equal(
+            parse_key(key, req)?,
+            value.trim().to_string(),
+        ));
+    }
+    if let Some(open) = req.find('(') {
+        let Some(values) = req[open + 1..].strip_suffix(')') else {
+            bail!("Missing ')' in selector requirement '{}'", req);
+        };
+        let values: BTreeSet<String> = values
+            .split(',')
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+            .collect();
+        let mut head = req[..open].split_whitespace();
+        return match (head.next(), head.next(), head.next()) {
+            (Some(key), Some("in"), None) => Ok(Expression::In(key.to_string(), values)),
+            (Some(key), Some("notin"), None) => Ok(Expression::NotIn(key.to_string(), values)),
+            _ => bail!("Invalid set-based selector requirement '{}'", req),
+        };
+    }
+    Ok(Expression::Exists(parse_key(req, req)?))
+}
+
+fn parse_key(key: &str, req: &str) -> Result<String> {
+    let key = key.trim();
+    if key.is_empty() || key.contains(char::is_whitespace) {
+        bail!("Invalid label key in selector requirement '{}'", req);
+    }
+    Ok(key.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn labels(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    fn namespace(pairs: &[(&str, &str)]) -> Namespace {
+        named_namespace("target", pairs)
+    }
+
+    fn named_namespace(name: &str, pairs: &[(&str, &str)]) -> Namespace {
+        let mut ns = Namespace::default();
+        ns.metadata.name = Some(name.to_string());
+        ns.metadata.labels = Some(labels(pairs));
+        ns
+    }
+
+    #[test]
+    fn test_parse_selector_equality() {
+        let sel = parse_selector("team=payments, env==prod").unwrap();
+        assert!(sel.matches(&labels(&[("team", "payments"), ("env", "prod")])));
+        assert!(!sel.matches(&labels(&[("team", "payments")])));
+    }
+
+    #[test]
+    fn test_parse_selector_set_based() {
+        let sel = parse_selector("env in (prod, staging),tier notin (db),!legacy,team").unwrap();
+        assert!(sel.matches(&labels(&[("env", "staging"), ("team", "x")])));
+        assert!(!sel.matches(&labels(&[("env", "dev"), ("team", "x")])));
+        assert!(!sel.matches(&labels(&[("env", "prod"), ("team", "x"), ("tier", "db")])));
+        assert!(!sel.matches(&labels(&[("env", "prod"), ("team", "x"), ("legacy", "")])));
+        assert!(!sel.matches(&labels(&[("env", "prod")])));
+    }
+
+    #[test]
+    fn test_parse_selector_not_equal() {
+        let sel = parse_selector("team!=payments").unwrap();
+        assert!(sel.matches(&labels(&[])));
+        assert!(!sel.matches(&labels(&[("team", "payments")])));
+    }
+
+    #[test]
+    fn test_parse_selector_invalid() {
+        assert!(parse_selector("env in (prod").is_err());
+        assert!(parse_selector("env foo (prod)").is_err());
+        assert!(parse_selector("=prod").is_err());
+    }
+
+    #[test]
+    fn test_target_without_selector_matches_all() {
+        let target = Target::from_meta(
+            &ObjectMeta::default(),
+            SproutKind::ConfigMap,
+            &Config::default(),
+        )
+        .unwrap();
+        assert!(target.matches(&namespace(&[])));
+    }
+
+    #[test]
+    fn test_target_with_selector() {
+        let meta = ObjectMeta {
+            annotations: Some(labels(&[(NAMESPACE_SELECTOR_KEY, "team=payments")])),
+            ..Default::default()
+        };
+        let target = Target::from_meta(&meta, SproutKind::ConfigMap, &Config::default()).unwrap();
+        assert!(target.matches(&namespace(&[("team", "payments")])));
+        assert!(!target.matches(&namespace(&[("team", "billing")])));
+    }
+
+    #[test]
+    fn test_target_namespace_ignore() {
+        let meta = ObjectMeta {
+            name: Some("shared".to_string()),
+            namespace: Some("source".to_string()),
+            ..Default::default()
+        };
+        let target = Target::from_meta(&meta, SproutKind::ConfigMap, &Config::default()).unwrap();
+        let mut ns = named_namespace("app", &[]);
+        ns.metadata.annotations = Some(labels(&[(IGNORE_KEY, "true")]));
+        assert!(!target.matches(&ns));
+        ns.metadata.annotations = Some(labels(&[(IGNORE_KEY, "other/config, source/shared")]));
+        assert!(!target.matches(&ns));
+        ns.metadata.annotations = Some(labels(&[(IGNORE_KEY, "other/config")]));
+        assert!(target.matches(&ns));
+        ns.metadata.annotations = Some(labels(&[(IGNORE_KEY, "ConfigMap/source/shared")]));
+        assert!(!target.matches(&ns));
+        ns.metadata.annotations = Some(labels(&[(IGNORE_KEY, "Secret/source/shared")]));
+        assert!(target.matches(&ns));
+    }
+
+    #[test]
+    fn test_target_namespace_ignore_cluster_seed() {
+        let spec = TargetSpec::default();
+        let config = Config::default();
+        let target = Target::from_spec("proxy".to_string(), SproutKind::Secret, &spec, &config);
+        let target = target.unwrap();
+        let mut ns = named_namespace("app", &[]);
+        ns.metadata.annotations = Some(labels(&[(IGNORE_KEY, "Secret/proxy")]));
+        assert!(!target.matches(&ns));
+        ns.metadata.annotations = Some(labels(&[(IGNORE_KEY, "ConfigMap/proxy")]));
+        assert!(target.matches(&ns));
+        ns.metadata.annotations = Some(labels(&[(IGNORE_KEY, "proxy")]));
+        assert!(!target.matches(&ns));
+    }
+
+    #[test]
+    fn test_target_opt_in() {
+        let config = Config {
+            opt_in: true,
+            ..Config::default()
+        };
+        let target =
+            Target::from_meta(&ObjectMeta::default(), SproutKind::ConfigMap, &config).unwrap();
+        assert!(target.matches(&named_namespace("app", &[(RECEIVE_KEY, "true")])));
+        assert!(!target.matches(&named_namespace("app", &[(RECEIVE_KEY, "false")])));
+        assert!(!target.matches(&named_namespace("app", &[])));
+    }
+
+    #[test]
+    fn test_target_explicit_list() {
+        let meta = ObjectMeta {
+            annotations: Some(labels(&[(TARGETS_KEY, "ns-a, ns-b,,kube-system")])),
+            ..Default::default()
+        };
+        let target = Target::from_meta(&meta, SproutKind::ConfigMap, &Config::default()).unwrap();
+        assert!(target.matches(&named_namespace("ns-a", &[])));
+        assert!(target.matches(&named_namespace("ns-b", &[])));
+        assert!(!target.matches(&named_namespace("ns-c", &[])));
+        assert!(!target.matches(&named_namespace("kube-system", &[])));
+    }
+
+    #[test]
+    fn test_pattern_glob() {
+        let pattern = Pattern::parse("app-*").unwrap();
+        assert!(pattern.matches("app-frontend"));
+        assert!(!pattern.matches("my-app-frontend"));
+        assert!(Pattern::parse("team-?").unwrap().matches("team-a"));
+        assert!(Pattern::parse("a.b").unwrap().matches("a.b"));
+        assert!(!Pattern::parse("a.b").unwrap().matches("axb"));
+    }
+
+    #[test]
+    fn test_pattern_regex() {
+        let pattern = Pattern::parse("/^team-(a|b)$/").unwrap();
+        assert!(pattern.matches("team-a"));
+        assert!(!pattern.matches("team-c"));
+        assert!(Pattern::parse("/team-(/").is_err());
+    }
+
+    #[test]
+    fn test_parse_patterns_keeps_commas_in_regex() {
+        let patterns = parse_patterns("app-*, /^team-[a-z]{1,3}$/ ,/^(x|y)$/").unwrap();
+        assert_eq!(patterns.len(), 3);
+        assert!(patterns[0].matches("app-a"));
+        assert!(patterns[1].matches("team-abc"));
+        assert!(!patterns[1].matches("team-abcd"));
+        assert!(patterns[2].matches("y"));
+        assert!(parse_patterns("/^team-[a-z]{1,3}$").is_err());
+        assert!(parse_patterns("/a/b/").is_err());
+    }
+
+    #[test]
+    fn test_target_include_exclude() {
+        let meta = ObjectMeta {
+            annotations: Some(labels(&[
+                (INCLUDE_KEY, "app-*, web-*"),
+                (EXCLUDE_KEY, "app-legacy"),
+            ])),
+            ..Default::default()
+        };
+        let target = Target::from_meta(&meta, SproutKind::ConfigMap, &Config::default()).unwrap();
+        assert!(target.matches(&named_namespace("app-frontend", &[])));
+        assert!(target.matches(&named_namespace("web-shop", &[])));
+        assert!(!target.matches(&named_namespace("app-legacy", &[])));
+        assert!(!target.matches(&named_namespace("default", &[])));
+    }
+
+    #[test]
+    fn test_target_default_exclusions() {
+        let target = Target::from_meta(
+            &ObjectMeta::default(),
+            SproutKind::ConfigMap,
+            &Config::default(),
+        )
+        .unwrap();
+        assert!(!target.matches(&named_namespace("kube-system", &[])));
+        assert!(!target.matches(&named_namespace("kube-node-lease", &[])));
+        assert!(target.matches(&named_namespace("default", &[])));
+
+        let config = Config {
+            excluded_namespaces: vec![],
+            ..Config::default()
+        };
+        let target =
+            Target::from_meta(&ObjectMeta::default(), SproutKind::ConfigMap, &config).unwrap();
+        assert!(target.matches(&named_namespace("kube-system", &[])));
+    }
+}
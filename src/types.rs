@@ -1,5 +1,261 @@
 // Copyright 2025, Jeroen van Erp <jeroen@geeko.me>
 // SPDX-License-Identifier: Apache-2.0
+use std::collections::BTreeMap;
 
-// src/types.rs
-// Placeholder for shared types or CRD definitions.
+use k8s_openapi::ByteString;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+use kube::CustomResource;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::sprout::kind::SproutKind;
+
+/// Requests a sprout of a requestable seed in the namespace the request is created in.
+#[derive(CustomResource, Deserialize, Serialize, Clone, Debug, PartialEq, JsonSchema)]
+#[kube(
+    group = "sprouter.geeko.me",
+    version = "v1alpha1",
+    kind = "SproutRequest",
+    namespaced,
+    status = "SproutRequestStatus",
+    shortname = "sprq",
+    printcolumn = r#"{"name":"Seed","type":"string","jsonPath":".spec.seed"}"#,
+    printcolumn = r#"{"name":"Kind","type":"string","jsonPath":".spec.kind"}"#,
+    printcolumn = r#"{"name":"Ready","type":"boolean","jsonPath":".status.ready"}"#,
+    printcolumn = r#"{"name":"Error","type":"string","jsonPath":".status.error"}"#
+)]
+#[serde(rename_all = "camelCase")]
+pub struct SproutRequestSpec {
+    /// The seed to sprout, as `namespace/name`.
+    pub seed: String,
+    /// The kind of the seed.
+    pub kind: SproutKind,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SproutRequestStatus {
+    /// Whether the sprout is present and up to date.
+    pub ready: bool,
+    /// The hash of the seed data the sprout was grown from.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed_hash: Option<String>,
+    /// Why the sprout could not be grown.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl SproutRequestStatus {
+    pub fn ready(seed_hash: Option<String>) -> Self {
+        Self {
+            ready: true,
+            seed_hash,
+            error: None,
+        }
+    }
+
+    pub fn failed(error: impl Into<String>) -> Self {
+        Self {
+            ready: false,
+            seed_hash: None,
+            error: Some(error.into()),
+        }
+    }
+}
+
+/// Declares how source objects selected by the policy propagate, as an alternative to annotating
+/// the source objects themselves.
+#[derive(CustomResource, Deserialize, Serialize, Clone, Debug, PartialEq, JsonSchema)]
+#[kube(
+    group = "sprouter.geeko.me",
+    version = "v1alpha1",
+    kind = "SproutPolicy",
+    shortname = "sprp",
+    printcolumn = r#"{"name":"Kind","type":"string","jsonPath":".spec.source.kind"}"#,
+    printcolumn = r#"{"name":"Namespace","type":"string","jsonPath":".spec.source.namespace"}"#,
+    printcolumn = r#"{"name":"Name","type":"string","jsonPath":".spec.source.name"}"#
+)]
+#[serde(rename_all = "camelCase")]
+pub struct SproutPolicySpec {
+    /// The source objects that become seeds.
+    pub source: PolicySource,
+    /// The namespaces the seeds sprout into.
+    #[serde(default)]
+    pub targets: TargetSpec,
+    /// Whether seeds can be requested with a SproutRequest.
+    #[serde(default)]
+    pub requestable: bool,
+    /// When existing sprouts are updated.
+    #[serde(default)]
+    pub update_strategy: UpdateStrategy,
+    /// What happens to sprouts that were changed and no longer match their seed.
+    #[serde(default)]
+    pub drift_policy: DriftPolicy,
+    /// Changes applied to sprouts.
+    #[serde(default)]
+    pub transform: Transform,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicySource {
+    /// The kind of the source objects.
+    pub kind: SproutKind,
+    /// The namespace of the source objects, any namespace if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+    /// The name of the source objects, any name if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Label selector the source objects must match, e.g. `app=shared`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label_selector: Option<String>,
+}
+
+/// The namespaces a seed sprouts into. Unset fields do not restrict the target namespaces.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TargetSpec {
+    /// Explicit list of namespaces.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub namespaces: Option<Vec<String>>,
+    /// Label selector namespaces must match, e.g. `team=payments`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub namespace_selector: Option<String>,
+    /// Namespace name globs (or `/regex/`) to include.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub include: Vec<String>,
+    /// Namespace name globs (or `/regex/`) to exclude.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<String>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq, JsonSchema)]
+pub enum UpdateStrategy {
+    /// Sprouts are updated whenever the seed changes.
+    #[default]
+    Always,
+    /// Sprouts are only created, existing sprouts are left untouched.
+    CreateOnly,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq, JsonSchema)]
+pub enum DriftPolicy {
+    /// Sprouts that were changed are restored to match the seed.
+    #[default]
+    Revert,
+    /// Sprouts that were changed are left as they are, and reported as drifted.
+    Flag,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Transform {
+    /// Labels added to every sprout.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub labels: BTreeMap<String, String>,
+    /// Annotations added to every sprout.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub annotations: BTreeMap<String, String>,
+}
+
+impl Transform {
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty() && self.annotations.is_empty()
+    }
+}
+
+/// A cluster-scoped seed carrying its data inline, sprouted as a ConfigMap or Secret into every
+/// selected namespace.
+#[derive(CustomResource, Deserialize, Serialize, Clone, Debug, PartialEq, JsonSchema)]
+#[kube(
+    group = "sprouter.geeko.me",
+    version = "v1alpha1",
+    kind = "ClusterSeed",
+    status = "SproutReport",
+    shortname = "csd",
+    printcolumn = r#"{"name":"Kind","type":"string","jsonPath":".spec.kind"}"#,
+    printcolumn = r#"{"name":"Ready","type":"string","jsonPath":".status.ready"}"#
+)]
+#[serde(rename_all = "camelCase")]
+pub struct ClusterSeedSpec {
+    /// The kind of the sprouts.
+    pub kind: SproutKind,
+    /// The data of the sprouts.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub data: BTreeMap<String, String>,
+    /// The binary data of the sprouts, base64 encoded.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    #[schemars(with = "BTreeMap<String, String>")]
+    pub binary_data: BTreeMap<String, ByteString>,
+    /// The namespaces the seed sprouts into.
+    #[serde(default)]
+    pub targets: TargetSpec,
+    /// What happens to sprouts that were changed and no longer match the seed.
+    #[serde(default)]
+    pub drift_policy: DriftPolicy,
+}
+
+/// Reports where the sprouts of a ConfigMap or Secret seed in the same namespace grew.
+#[derive(CustomResource, Deserialize, Serialize, Clone, Debug, PartialEq, JsonSchema)]
+#[kube(
+    group = "sprouter.geeko.me",
+    version = "v1alpha1",
+    kind = "SproutStatus",
+    namespaced,
+    status = "SproutReport",
+    shortname = "sprs",
+    printcolumn = r#"{"name":"Seed","type":"string","jsonPath":".spec.seed"}"#,
+    printcolumn = r#"{"name":"Kind","type":"string","jsonPath":".spec.kind"}"#,
+    printcolumn = r#"{"name":"Ready","type":"string","jsonPath":".status.ready"}"#
+)]
+#[serde(rename_all = "camelCase")]
+pub struct SproutStatusSpec {
+    /// The seed, as `namespace/name`.
+    pub seed: String,
+    /// The kind of the seed.
+    pub kind: SproutKind,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SproutReport {
+    /// The number of sprouts that are up to date, out of all sprouts, e.g. `3/4`.
+    pub ready: String,
+    /// The hash of the seed data.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed_hash: Option<String>,
+    /// The sprouts of the seed, by namespace.
+    #[serde(default)]
+    pub sprouts: Vec<SproutInventory>,
+}
+
+impl SproutReport {
+    pub fn new(seed_hash: Option<String>, mut sprouts: Vec<SproutInventory>) -> Self {
+        sprouts.sort_by(|a, b| a.namespace.cmp(&b.namespace));
+        let ready = sprouts.iter().filter(|s| s.ready).count();
+        Self {
+            ready: format!("{}/{}", ready, sprouts.len()),
+            seed_hash,
+            sprouts,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SproutInventory {
+    /// The namespace of the sprout.
+    pub namespace: String,
+    /// Whether the sprout is present and grown from the current seed data.
+    pub ready: bool,
+    /// The hash of the seed data the sprout was grown from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub observed_hash: Option<String>,
+    /// When the state of the sprout last changed, which an unchanged resync leaves as is.
+    #[schemars(with = "String")]
+    pub last_sync: Time,
+    /// Why the sprout could not be grown.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
@@ -10,6 +10,18 @@ use sha2::Sha256;
 pub const ANNOTATION_KEY: &str = "sprouter.geeko.me/enabled";
 const SPROUT_KEY: &str = "sprouter.geeko.me/sprout-of";
 const SEED_HASH_KEY: &str = "sprouter.geeko.me/seed-hash";
+/// Labels every sprout, so the sprouts can be watched without watching every object.
+pub const SPROUT_LABEL_KEY: &str = "sprouter.geeko.me/sprout";
+pub const NAMESPACE_SELECTOR_KEY: &str = "sprouter.geeko.me/namespace-selector";
+pub const INCLUDE_KEY: &str = "sprouter.geeko.me/include";
+pub const EXCLUDE_KEY: &str = "sprouter.geeko.me/exclude";
+pub const TARGETS_KEY: &str = "sprouter.geeko.me/targets";
+pub const REQUESTABLE_KEY: &str = "sprouter.geeko.me/requestable";
+pub const IGNORE_KEY: &str = "sprouter.geeko.me/ignore";
+pub const RECEIVE_KEY: &str = "sprouter.geeko.me/receive";
+pub const DRIFT_POLICY_KEY: &str = "sprouter.geeko.me/drift-policy";
+/// Keeps a seed around until its sprouts are deleted.
+pub const FINALIZER: &str = "sprouter.geeko.me/sprouts";
 
 pub fn is_seed(meta: &ObjectMeta) -> bool {
     meta.annotations
@@ -19,6 +31,14 @@ pub fn is_seed(meta: &ObjectMeta) -> bool {
         .unwrap_or(false)
 }
 
+pub fn is_requestable(meta: &ObjectMeta) -> bool {
+    meta.annotations
+        .as_ref()
+        .and_then(|a| a.get(REQUESTABLE_KEY))
+        .map(|v| v == "true")
+        .unwrap_or(false)
+}
+
 pub fn is_sprout(meta: &ObjectMeta) -> bool {
     meta.annotations
         .as_ref()
@@ -26,6 +46,43 @@ pub fn is_sprout(meta: &ObjectMeta) -> bool {
         .is_some()
 }
 
+/// Whether the sprout carries the sprout label. Sprouts grown by older versions do not.
+pub fn has_sprout_label(meta: &ObjectMeta) -> bool {
+    meta.labels
+        .as_ref()
+        .and_then(|l| l.get(SPROUT_LABEL_KEY))
+        .is_some_and(|v| v == "true")
+}
+
+pub fn has_finalizer(meta: &ObjectMeta) -> bool {
+    meta.finalizers
+        .as_ref()
+        .is_some_and(|f| f.iter().any(|f| f == FINALIZER))
+}
+
+/// The finalizers of the object with or without the sprouter finalizer, or `None` if it already
+/// has or lacks it.
+pub fn toggle_finalizer(meta: &ObjectMeta, present: bool) -> Option<Vec<String>> {
+    if has_finalizer(meta) == present {
+        return None;
+    }
+    let mut finalizers = meta.finalizers.clone().unwrap_or_default();
+    if present {
+        finalizers.push(FINALIZER.to_string());
+    } else {
+        finalizers.retain(|f| f != FINALIZER);
+    }
+    Some(finalizers)
+}
+
+pub fn sprout_of(meta: &ObjectMeta) -> Option<&String> {
+    meta.annotations.as_ref().and_then(|a| a.get(SPROUT_KEY))
+}
+
+pub fn is_sprout_of(meta: &ObjectMeta, seed: &str) -> bool {
+    sprout_of(meta).map(|v| v == seed).unwrap_or(false)
+}
+
 pub fn is_sprout_recent(meta: &ObjectMeta, hash: &Option<String>) -> bool {
     meta.annotations
         .as_ref()
@@ -34,18 +91,41 @@ pub fn is_sprout_recent(meta: &ObjectMeta, hash: &Option<String>) -> bool {
         .unwrap_or(false)
 }
 
+/// The hash of the seed data a sprout was grown from.
+pub fn sprout_hash(meta: &ObjectMeta) -> Option<&String> {
+    meta.annotations.as_ref().and_then(|a| a.get(SEED_HASH_KEY))
+}
+
+/// The `namespace/name` reference of a seed, as recorded on its sprouts. ClusterSeeds have no
+/// namespace and are referenced by their name.
+pub fn seed_ref<K: kube::Resource>(r: &K) -> String {
+    seed_ref_of(r.meta())
+}
+
+pub fn seed_ref_of(meta: &ObjectMeta) -> String {
+    let name = meta.name.as_deref().unwrap_or_default();
+    match meta.namespace.as_deref() {
+        Some(ns) => format!("{}/{}", ns, name),
+        None => name.to_string(),
+    }
+}
+
 pub fn create_sprout<K>(r: K, hash: &Option<String>) -> K
 where
     K: kube::Resource<Scope = kube::core::NamespaceResourceScope> + Clone,
 {
     let mut res = r.clone();
-    let val = format!("{}/{}", r.namespace().unwrap_or_default(), r.name_any());
+    let val = seed_ref(&r);
     res.annotations_mut().remove(ANNOTATION_KEY);
     res.annotations_mut().insert(SPROUT_KEY.to_string(), val);
-    hash.as_ref().map(|h| {
+    res.labels_mut()
+        .insert(SPROUT_LABEL_KEY.to_string(), "true".to_string());
+    // The finalizer of the seed would keep its sprouts from being deleted
+    res.finalizers_mut().retain(|f| f != FINALIZER);
+    if let Some(h) = hash {
         res.annotations_mut()
             .insert(SEED_HASH_KEY.to_string(), h.to_string());
-    });
+    }
     res
 }
 
@@ -107,6 +187,55 @@ mod tests {
         assert!(!is_sprout(cm.meta()));
     }
 
+    #[test]
+    fn test_is_sprout_of() {
+        let mut cm = ConfigMap::default();
+        cm.metadata.annotations = Some(BTreeMap::from([(
+            SPROUT_KEY.to_string(),
+            "source/shared".to_string(),
+        )]));
+        assert!(is_sprout_of(cm.meta(), "source/shared"));
+        assert!(!is_sprout_of(cm.meta(), "other/shared"));
+    }
+
+    #[test]
+    fn test_create_sprout_is_labelled() {
+        let mut cm = ConfigMap::default();
+        cm.metadata.name = Some("shared".to_string());
+        cm.metadata.namespace = Some("source".to_string());
+        cm.metadata.finalizers = Some(vec![FINALIZER.to_string()]);
+        assert!(!has_sprout_label(cm.meta()));
+        let sprout = create_sprout(cm, &None);
+        assert!(has_sprout_label(sprout.meta()));
+        assert!(!has_finalizer(sprout.meta()));
+        assert!(is_sprout_of(sprout.meta(), "source/shared"));
+    }
+
+    #[test]
+    fn test_toggle_finalizer() {
+        let mut cm = ConfigMap::default();
+        assert_eq!(toggle_finalizer(cm.meta(), false), None);
+        assert_eq!(
+            toggle_finalizer(cm.meta(), true),
+            Some(vec![FINALIZER.to_string()])
+        );
+        cm.metadata.finalizers = Some(vec!["other".to_string(), FINALIZER.to_string()]);
+        assert_eq!(toggle_finalizer(cm.meta(), true), None);
+        assert_eq!(
+            toggle_finalizer(cm.meta(), false),
+            Some(vec!["other".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_seed_ref_of() {
+        let mut cm = ConfigMap::default();
+        cm.metadata.name = Some("shared".to_string());
+        assert_eq!(seed_ref_of(cm.meta()), "shared");
+        cm.metadata.namespace = Some("source".to_string());
+        assert_eq!(seed_ref_of(cm.meta()), "source/shared");
+    }
+
     #[test]
     fn test_is_sprout_recent_true() {
         let mut cm = ConfigMap::default();